@@ -0,0 +1,339 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    msg::InstantiateMsg,
+    state::{Config, CONFIG},
+    ContractError,
+};
+use cw2::set_contract_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const INSTANTIATE_AUCTION_REPLY_ID: u64 = 1;
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: info.sender,
+        bidding_code_id: msg.bidding_code_id,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new())
+}
+
+pub mod exec {
+    use bidding::{
+        msg::{AuctionMsg, InstantiateMsg},
+        CONTRACT_LABEL,
+    };
+    use cosmwasm_std::{to_json_binary, DepsMut, MessageInfo, Response, SubMsg, WasmMsg};
+
+    use crate::{
+        msg::ExecuteMsg::{self, *},
+        state::{Auction, CONFIG, PENDING},
+        ContractError,
+    };
+
+    use super::INSTANTIATE_AUCTION_REPLY_ID;
+
+    pub fn execute(
+        deps: DepsMut,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        match msg {
+            CreateAuction { auction, label } => create_auction(deps, info, auction, label),
+            UpdateCodeId { code_id } => update_code_id(deps, info, code_id),
+        }
+    }
+
+    pub fn create_auction(
+        deps: DepsMut,
+        info: MessageInfo,
+        mut auction: Box<AuctionMsg>,
+        label: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let sender = info.sender;
+
+        // the given owner or the creator owns the auction, never the factory
+        auction.owner.get_or_insert_with(|| sender.to_string());
+
+        let pending = Auction {
+            address: sender.clone(),
+            auction_id: 0,
+            creator: sender.clone(),
+            name: auction.name.clone(),
+            end: auction.end,
+        };
+        PENDING.save(deps.storage, &pending)?;
+
+        // the created contract hosts this auction only
+        let auction = InstantiateMsg {
+            creators: Some(vec![]),
+            auction: Some(auction),
+        };
+
+        let msg = WasmMsg::Instantiate {
+            admin: Some(config.owner.to_string()),
+            code_id: config.bidding_code_id,
+            msg: to_json_binary(&auction)?,
+            // escrows the budget of a reverse auction
+            funds: info.funds,
+            label: label.unwrap_or_else(|| CONTRACT_LABEL.into()),
+        };
+
+        let resp = Response::new()
+            .add_submessage(SubMsg::reply_on_success(msg, INSTANTIATE_AUCTION_REPLY_ID))
+            .add_attribute("action", "create_auction")
+            .add_attribute("sender", sender);
+
+        Ok(resp)
+    }
+
+    pub fn update_code_id(
+        deps: DepsMut,
+        info: MessageInfo,
+        code_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut config = CONFIG.load(deps.storage)?;
+
+        if config.owner != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        config.bidding_code_id = code_id;
+        CONFIG.save(deps.storage, &config)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_code_id")
+            .add_attribute("sender", info.sender)
+            .add_attribute("code_id", code_id.to_string());
+
+        Ok(resp)
+    }
+}
+
+pub mod reply {
+    use cosmwasm_std::{from_json, DepsMut, Reply, Response, StdError};
+    use cw_utils::parse_reply_instantiate_data;
+
+    use crate::{
+        state::{auctions, PENDING},
+        ContractError,
+    };
+
+    use super::INSTANTIATE_AUCTION_REPLY_ID;
+
+    pub fn reply(deps: DepsMut, reply: Reply) -> Result<Response, ContractError> {
+        match reply.id {
+            INSTANTIATE_AUCTION_REPLY_ID => auction_instantiated(deps, reply),
+            id => Err(ContractError::UnknownReplyIdErr { id }),
+        }
+    }
+
+    pub fn auction_instantiated(deps: DepsMut, reply: Reply) -> Result<Response, ContractError> {
+        let resp = parse_reply_instantiate_data(reply)?;
+        let address = deps.api.addr_validate(&resp.contract_address)?;
+        let data = resp
+            .data
+            .ok_or_else(|| StdError::generic_err("missing auction id"))?;
+
+        let mut auction = PENDING.load(deps.storage)?;
+        PENDING.remove(deps.storage);
+
+        auction.address = address.clone();
+        auction.auction_id = from_json(data)?;
+        auctions().save(deps.storage, &address, &auction)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "auction_instantiated")
+            .add_attribute("auction", address)
+            .add_attribute("creator", auction.creator);
+
+        Ok(resp)
+    }
+}
+
+pub mod query {
+    use bidding::{msg::StatusResp, state::BidStatus};
+    use cosmwasm_std::{to_json_binary, Addr, Binary, Deps, Order, StdResult};
+    use cw_storage_plus::Bound;
+
+    use crate::{
+        msg::{AuctionCursor, AuctionResp, AuctionsResp, ConfigResp, QueryMsg},
+        state::{self, auctions, CONFIG},
+    };
+    use QueryMsg::*;
+
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+
+    pub fn query(deps: Deps, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            Config {} => query_config(deps).and_then(|c| to_json_binary(&c)),
+
+            Auction { address } => query_auction(deps, &address).and_then(|a| to_json_binary(&a)),
+
+            Auctions {
+                status,
+                start_after,
+                limit,
+            } => query_auctions(deps, status, start_after, limit).and_then(|a| to_json_binary(&a)),
+
+            AuctionsByCreator {
+                creator,
+                status,
+                start_after,
+                limit,
+            } => query_auctions_by_creator(deps, &creator, status, start_after, limit)
+                .and_then(|a| to_json_binary(&a)),
+
+            AuctionsByEndTime {
+                status,
+                start_after,
+                limit,
+            } => query_auctions_by_end_time(deps, status, start_after, limit)
+                .and_then(|a| to_json_binary(&a)),
+        }
+    }
+
+    pub fn query_config(deps: Deps) -> StdResult<ConfigResp> {
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(ConfigResp {
+            owner: config.owner,
+            bidding_code_id: config.bidding_code_id,
+        })
+    }
+
+    pub fn query_auction(deps: Deps, address: &str) -> StdResult<AuctionResp> {
+        let address = deps.api.addr_validate(address)?;
+        let auction = auctions().load(deps.storage, &address)?;
+
+        auction_resp(deps, auction)
+    }
+
+    pub fn query_auctions(
+        deps: Deps,
+        status: Option<BidStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let min = start_after.as_ref().map(Bound::exclusive);
+
+        let iter = auctions().range(deps.storage, min, None, Order::Ascending);
+
+        collect_auctions(deps, iter, status, limit)
+    }
+
+    pub fn query_auctions_by_creator(
+        deps: Deps,
+        creator: &str,
+        status: Option<BidStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
+        let creator = deps.api.addr_validate(creator)?;
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let min = start_after.as_ref().map(Bound::exclusive);
+
+        let iter =
+            auctions()
+                .idx
+                .creator
+                .prefix(creator)
+                .range(deps.storage, min, None, Order::Ascending);
+
+        collect_auctions(deps, iter, status, limit)
+    }
+
+    pub fn query_auctions_by_end_time(
+        deps: Deps,
+        status: Option<BidStatus>,
+        start_after: Option<(u64, String)>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
+        let start_after = start_after
+            .map(|(end_time, addr)| deps.api.addr_validate(&addr).map(|addr| (end_time, addr)))
+            .transpose()?;
+        let min = start_after
+            .as_ref()
+            .map(|(end_time, addr)| Bound::exclusive((*end_time, addr)));
+
+        let iter = auctions()
+            .idx
+            .end_time
+            .range(deps.storage, min, None, Order::Ascending);
+
+        collect_auctions(deps, iter, status, limit)
+    }
+
+    // Scan up to limit auctions and keep those matching the status, which is queried
+    // from each auction, so the gas of a page is bounded whatever the filter
+    fn collect_auctions<'a>(
+        deps: Deps,
+        iter: impl Iterator<Item = StdResult<(Addr, state::Auction)>> + 'a,
+        status: Option<BidStatus>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let mut iter = iter.peekable();
+        let mut auctions = vec![];
+        let mut last = None;
+
+        for item in iter.by_ref().take(limit) {
+            let (_, auction) = item?;
+            last = Some(AuctionCursor {
+                address: auction.address.clone(),
+                end_time: auction.end_time(),
+            });
+
+            let auction = auction_resp(deps, auction)?;
+            if status
+                .as_ref()
+                .is_none_or(|status| *status == auction.status)
+            {
+                auctions.push(auction);
+            }
+        }
+
+        let next = iter.peek().and(last);
+
+        Ok(AuctionsResp { auctions, next })
+    }
+
+    fn auction_resp(deps: Deps, auction: state::Auction) -> StdResult<AuctionResp> {
+        let resp: StatusResp = deps.querier.query_wasm_smart(
+            &auction.address,
+            &bidding::msg::QueryMsg::Status {
+                auction_id: auction.auction_id,
+            },
+        )?;
+
+        Ok(AuctionResp {
+            address: auction.address,
+            auction_id: auction.auction_id,
+            creator: auction.creator,
+            name: auction.name,
+            end: auction.end,
+            status: resp.status,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use bidding::state::BidStatus;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+use cw_utils::Expiration;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub bidding_code_id: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Instantiate a new auction owned by `auction.owner`, the sender if missing
+    CreateAuction {
+        auction: Box<bidding::msg::AuctionMsg>,
+        label: Option<String>,
+    },
+    /// Use another bidding code id for new auctions, only the factory owner
+    UpdateCodeId { code_id: u64 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResp)]
+    Config {},
+    #[returns(AuctionResp)]
+    Auction { address: String },
+    /// Auctions ordered by address, at most `limit` auctions are scanned per page so a
+    /// status filter may return fewer, continue after `next`
+    #[returns(AuctionsResp)]
+    Auctions {
+        status: Option<BidStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Auctions of the creator ordered by address
+    #[returns(AuctionsResp)]
+    AuctionsByCreator {
+        creator: String,
+        status: Option<BidStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Auctions ordered by the end time set at creation then address, auctions without a
+    /// time end come last. Extensions and penny countdowns do not reorder an auction
+    #[returns(AuctionsResp)]
+    AuctionsByEndTime {
+        status: Option<BidStatus>,
+        start_after: Option<(u64, String)>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResp {
+    pub owner: Addr,
+    pub bidding_code_id: u64,
+}
+
+#[cw_serde]
+pub struct AuctionResp {
+    pub address: Addr,
+    pub auction_id: u64,
+    pub creator: Addr,
+    pub name: String,
+    // the end set at creation, not updated by extensions
+    pub end: Option<Expiration>,
+    // the current status queried from the auction
+    pub status: BidStatus,
+}
+
+#[cw_serde]
+pub struct AuctionsResp {
+    pub auctions: Vec<AuctionResp>,
+    // the last auction scanned, None once every auction is scanned
+    pub next: Option<AuctionCursor>,
+}
+
+/// Where the next page starts, `address` or `(end_time, address)` by end time
+#[cw_serde]
+pub struct AuctionCursor {
+    pub address: Addr,
+    pub end_time: u64,
+}
@@ -0,0 +1,172 @@
+mod tests;
+
+use bidding::state::BidStatus;
+use cosmwasm_std::{Addr, StdResult};
+use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
+
+use crate::{
+    execute, instantiate,
+    msg::{AuctionResp, AuctionsResp, ConfigResp, ExecuteMsg, InstantiateMsg, QueryMsg},
+    query, reply, ContractError,
+};
+
+#[derive(Debug)]
+pub struct FactoryContract(Addr);
+
+impl FactoryContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn store_code(app: &mut App) -> u64 {
+        let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+        app.store_code(Box::new(contract))
+    }
+
+    pub fn store_bidding_code(app: &mut App) -> u64 {
+        let contract = ContractWrapper::new(bidding::execute, bidding::instantiate, bidding::query);
+        app.store_code(Box::new(contract))
+    }
+
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App,
+        code_id: u64,
+        sender: Addr,
+        bidding_code_id: u64,
+    ) -> Result<FactoryContract, ContractError> {
+        app.instantiate_contract(
+            code_id,
+            sender,
+            &InstantiateMsg { bidding_code_id },
+            &[],
+            "factory",
+            None,
+        )
+        .map_err(|e| e.downcast().unwrap())
+        .map(FactoryContract)
+    }
+
+    /// Create an auction and return its address from the reply attributes
+    #[track_caller]
+    pub fn create_auction(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        auction: bidding::msg::AuctionMsg,
+    ) -> Result<Addr, ContractError> {
+        let resp: AppResponse = app
+            .execute_contract(
+                sender,
+                self.addr(),
+                &ExecuteMsg::CreateAuction {
+                    auction: Box::new(auction),
+                    label: None,
+                },
+                &[],
+            )
+            .map_err(|e| e.downcast::<ContractError>().unwrap())?;
+
+        let address = resp
+            .events
+            .iter()
+            .flat_map(|e| &e.attributes)
+            .find(|a| a.key == "auction")
+            .map(|a| Addr::unchecked(&a.value))
+            .unwrap();
+
+        Ok(address)
+    }
+
+    #[track_caller]
+    pub fn update_code_id(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        code_id: u64,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::UpdateCodeId { code_id },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    pub fn query_config(&self, app: &App) -> StdResult<ConfigResp> {
+        app.wrap()
+            .query_wasm_smart(self.addr(), &QueryMsg::Config {})
+    }
+
+    pub fn query_auction(&self, app: &App, address: &Addr) -> StdResult<AuctionResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Auction {
+                address: address.to_string(),
+            },
+        )
+    }
+
+    pub fn query_auctions(
+        &self,
+        app: &App,
+        status: Option<BidStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Auctions {
+                status,
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn query_auctions_by_creator(
+        &self,
+        app: &App,
+        creator: &Addr,
+        status: Option<BidStatus>,
+    ) -> StdResult<AuctionsResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::AuctionsByCreator {
+                creator: creator.to_string(),
+                status,
+                start_after: None,
+                limit: None,
+            },
+        )
+    }
+
+    pub fn query_auctions_by_end_time(
+        &self,
+        app: &App,
+        start_after: Option<(u64, String)>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::AuctionsByEndTime {
+                status: None,
+                start_after,
+                limit,
+            },
+        )
+    }
+}
+
+pub fn alice() -> Addr {
+    Addr::unchecked("sei18rszd3tmgpjvjwq2qajtmn5jqvtscd2yuygl4z")
+}
+
+pub fn bob() -> Addr {
+    Addr::unchecked("sei1aan9kqywf4rf274cal0hj6eyly6wu0uv7edxy2")
+}
+
+pub fn owner() -> Addr {
+    Addr::unchecked("sei1zj6fjsc2gkce878ukzg6g9wy8cl8p554dlggxd")
+}
@@ -0,0 +1,227 @@
+use bidding::{msg::AuctionMsg, state::BidStatus, ATOM_DENOM};
+use cosmwasm_std::{coins, Timestamp};
+use cw20::UncheckedDenom;
+use cw_multi_test::{App, Executor};
+use cw_utils::Expiration;
+
+use crate::{
+    multitest::{alice, bob, owner},
+    ContractError,
+};
+
+use super::FactoryContract;
+
+fn auction(name: &str, end: Option<Timestamp>) -> AuctionMsg {
+    AuctionMsg {
+        end: end.map(Expiration::AtTime),
+        ..AuctionMsg::new(name.into(), UncheckedDenom::Native(ATOM_DENOM.into()), 1, 1)
+    }
+}
+
+#[test]
+fn create_auction_should_works() {
+    let mut app = App::default();
+
+    let bidding_code_id = FactoryContract::store_bidding_code(&mut app);
+    let code_id = FactoryContract::store_code(&mut app);
+    let factory =
+        FactoryContract::instantiate(&mut app, code_id, owner(), bidding_code_id).unwrap();
+
+    let address = factory
+        .create_auction(&mut app, alice(), auction("punk", None))
+        .unwrap();
+
+    let resp = factory.query_auction(&app, &address).unwrap();
+    assert_eq!(resp.creator, alice());
+    assert_eq!(resp.name, "punk");
+    assert_eq!(resp.auction_id, 1);
+    assert_eq!(resp.status, BidStatus::Opening {});
+
+    // the creator owns the auction and the factory owner is its admin
+    let info = app.wrap().query_wasm_contract_info(&address).unwrap();
+    assert_eq!(info.code_id, bidding_code_id);
+    assert_eq!(info.admin, Some(owner().to_string()));
+
+    app.execute_contract(
+        alice(),
+        address.clone(),
+        &bidding::msg::ExecuteMsg::Close {
+            auction_id: 1,
+            reveal: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let resp = factory.query_auction(&app, &address).unwrap();
+    assert_eq!(resp.status, BidStatus::Closed {});
+}
+
+#[test]
+fn create_auction_for_owner_should_works() {
+    let mut app = App::default();
+
+    let bidding_code_id = FactoryContract::store_bidding_code(&mut app);
+    let code_id = FactoryContract::store_code(&mut app);
+    let factory =
+        FactoryContract::instantiate(&mut app, code_id, owner(), bidding_code_id).unwrap();
+
+    // a deployer creates the auction on behalf of the seller
+    let msg = AuctionMsg {
+        owner: Some(bob().to_string()),
+        ..auction("punk", None)
+    };
+    let address = factory.create_auction(&mut app, alice(), msg).unwrap();
+
+    let resp = factory.query_auction(&app, &address).unwrap();
+    assert_eq!(resp.creator, alice());
+
+    let close = bidding::msg::ExecuteMsg::Close {
+        auction_id: 1,
+        reveal: None,
+    };
+    let err: bidding::ContractError = app
+        .execute_contract(alice(), address.clone(), &close, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, bidding::ContractError::Unauthorized {});
+
+    app.execute_contract(bob(), address, &close, &[]).unwrap();
+}
+
+#[test]
+fn query_auctions_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &bob(), coins(10, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let bidding_code_id = FactoryContract::store_bidding_code(&mut app);
+    let code_id = FactoryContract::store_code(&mut app);
+    let factory =
+        FactoryContract::instantiate(&mut app, code_id, owner(), bidding_code_id).unwrap();
+
+    let now = app.block_info().time;
+    let first = factory
+        .create_auction(
+            &mut app,
+            alice(),
+            auction("first", Some(now.plus_seconds(300))),
+        )
+        .unwrap();
+    let second = factory
+        .create_auction(&mut app, bob(), auction("second", None))
+        .unwrap();
+    let third = factory
+        .create_auction(
+            &mut app,
+            alice(),
+            auction("third", Some(now.plus_seconds(100))),
+        )
+        .unwrap();
+
+    let resp = factory.query_auctions(&app, None, None, None).unwrap();
+    assert_eq!(resp.auctions.len(), 3);
+
+    // paginate by address
+    let resp = factory.query_auctions(&app, None, None, Some(2)).unwrap();
+    assert_eq!(resp.auctions.len(), 2);
+    let start_after = resp.auctions[1].address.to_string();
+    let resp = factory
+        .query_auctions(&app, None, Some(start_after), Some(2))
+        .unwrap();
+    assert_eq!(resp.auctions.len(), 1);
+
+    let resp = factory
+        .query_auctions_by_creator(&app, &alice(), None)
+        .unwrap();
+    let addresses: Vec<_> = resp.auctions.into_iter().map(|a| a.address).collect();
+    assert_eq!(addresses.len(), 2);
+    assert!(addresses.contains(&first) && addresses.contains(&third));
+
+    // ordered by end time, auctions without a time end come last
+    let resp = factory
+        .query_auctions_by_end_time(&app, None, Some(2))
+        .unwrap();
+    let addresses: Vec<_> = resp.auctions.into_iter().map(|a| a.address).collect();
+    assert_eq!(addresses, vec![third.clone(), first]);
+
+    let start_after = Some((now.plus_seconds(300).seconds(), addresses[1].to_string()));
+    let resp = factory
+        .query_auctions_by_end_time(&app, start_after, None)
+        .unwrap();
+    let addresses: Vec<_> = resp.auctions.into_iter().map(|a| a.address).collect();
+    assert_eq!(addresses, vec![second.clone()]);
+
+    // filter by the status of the auction
+    app.execute_contract(
+        bob(),
+        second,
+        &bidding::msg::ExecuteMsg::Close {
+            auction_id: 1,
+            reveal: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let resp = factory
+        .query_auctions(&app, Some(BidStatus::Closed {}), None, None)
+        .unwrap();
+    assert_eq!(resp.auctions.len(), 1);
+    assert_eq!(resp.auctions[0].creator, bob());
+
+    let resp = factory
+        .query_auctions_by_creator(&app, &alice(), Some(BidStatus::Closed {}))
+        .unwrap();
+    assert!(resp.auctions.is_empty());
+    assert_eq!(resp.next, None);
+
+    // a filtered page scans at most limit auctions, the cursor continues the scan
+    let mut closed = vec![];
+    let mut start_after = None;
+    loop {
+        let resp = factory
+            .query_auctions(&app, Some(BidStatus::Closed {}), start_after, Some(1))
+            .unwrap();
+        assert!(resp.auctions.len() <= 1);
+        closed.extend(resp.auctions);
+
+        match resp.next {
+            Some(next) => start_after = Some(next.address.to_string()),
+            None => break,
+        }
+    }
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].creator, bob());
+}
+
+#[test]
+fn update_code_id_should_works() {
+    let mut app = App::default();
+
+    let bidding_code_id = FactoryContract::store_bidding_code(&mut app);
+    let code_id = FactoryContract::store_code(&mut app);
+    let factory =
+        FactoryContract::instantiate(&mut app, code_id, owner(), bidding_code_id).unwrap();
+
+    let err = factory.update_code_id(&mut app, alice(), 42).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let new_code_id = FactoryContract::store_bidding_code(&mut app);
+    factory
+        .update_code_id(&mut app, owner(), new_code_id)
+        .unwrap();
+
+    let config = factory.query_config(&app).unwrap();
+    assert_eq!(config.bidding_code_id, new_code_id);
+
+    let address = factory
+        .create_auction(&mut app, alice(), auction("punk", None))
+        .unwrap();
+    let info = app.wrap().query_wasm_contract_info(&address).unwrap();
+    assert_eq!(info.code_id, new_code_id);
+}
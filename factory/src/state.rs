@@ -0,0 +1,71 @@
+use cosmwasm_schema::cw_serde;
+/// Define Factory contract state and storage item
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use cw_utils::Expiration;
+
+#[cw_serde]
+pub struct Config {
+    // factory owner, also the admin of every created auction
+    pub owner: Addr,
+    // code id of the bidding contract
+    pub bidding_code_id: u64,
+}
+
+#[cw_serde]
+pub struct Auction {
+    // auction contract address
+    pub address: Addr,
+    // the auction id inside the auction contract
+    pub auction_id: u64,
+    // the sender of create auction, the owner unless another one was given
+    pub creator: Addr,
+    // bidding name
+    pub name: String,
+    // the end set at creation, anti-sniping may extend it later
+    pub end: Option<Expiration>,
+}
+
+impl Auction {
+    /// The end time in seconds the auction is indexed by, auctions without a time end sort last
+    pub fn end_time(&self) -> u64 {
+        match self.end {
+            Some(Expiration::AtTime(time)) => time.seconds(),
+            _ => u64::MAX,
+        }
+    }
+}
+
+pub struct AuctionIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Auction, &'a Addr>,
+    pub end_time: MultiIndex<'a, u64, Auction, &'a Addr>,
+}
+
+impl<'a> IndexList<Auction> for AuctionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Auction>> + '_> {
+        let v: Vec<&dyn Index<Auction>> = vec![&self.creator, &self.end_time];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn auctions<'a>() -> IndexedMap<'a, &'a Addr, Auction, AuctionIndexes<'a>> {
+    let indexes = AuctionIndexes {
+        creator: MultiIndex::new(
+            |_pk, auction| auction.creator.clone(),
+            "auctions",
+            "auctions__creator",
+        ),
+        end_time: MultiIndex::new(
+            |_pk, auction| auction.end_time(),
+            "auctions",
+            "auctions__end_time",
+        ),
+    };
+
+    IndexedMap::new("auctions", indexes)
+}
+
+// Define the state storage
+pub const CONFIG: Item<Config> = Item::new("config");
+// the auction being instantiated, its address is filled in the reply
+pub const PENDING: Item<Auction> = Item::new("pending");
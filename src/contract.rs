@@ -1,9 +1,14 @@
-use cosmwasm_std::{coin, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    coin, to_json_binary, Addr, Coin, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+use cw20::Denom;
+use cw_utils::{nonpayable, Duration, PaymentError, Scheduled};
 
 use crate::{
-    msg::InstantiateMsg,
-    state::{State, STATE},
-    ContractError, ATOM_DENOM,
+    helper::{collect_coins, validiate_denom_name},
+    msg::{AuctionMsg, InstantiateMsg},
+    state::{AuctionKind, Config, State, AUCTION_SEQ, CONFIG, MAX_BPS, STATE},
+    ContractError,
 };
 use cw2::set_contract_version;
 
@@ -13,34 +18,177 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let state = State::new(
-        info.sender,
+    let creators = msg
+        .creators
+        .map(|creators| {
+            creators
+                .iter()
+                .map(|creator| deps.api.addr_validate(creator))
+                .collect::<StdResult<Vec<_>>>()
+        })
+        .transpose()?;
+
+    let config = Config {
+        admin: info.sender.clone(),
+        creators,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    AUCTION_SEQ.save(deps.storage, &0)?;
+
+    let mut resp = Response::new();
+
+    if let Some(auction) = msg.auction {
+        let auction_id = open_auction(deps, &env, info.sender, &info.funds, *auction)?;
+
+        resp = resp
+            .set_data(to_json_binary(&auction_id)?)
+            .add_attribute("auction_id", auction_id.to_string());
+    } else {
+        nonpayable(&info)?;
+    }
+
+    Ok(resp)
+}
+
+/// Open a new auction, the sender owns it unless another owner is given.
+/// The funds escrow the budget of a reverse auction, any other kind takes no funds
+pub fn open_auction(
+    deps: DepsMut,
+    env: &Env,
+    sender: Addr,
+    funds: &[Coin],
+    msg: AuctionMsg,
+) -> Result<u64, ContractError> {
+    exec::validiate_kind(&msg)?;
+
+    let (denom, cw20) = match msg.denom.into_checked(deps.as_ref())? {
+        Denom::Native(denom) => {
+            validiate_denom_name(&denom)?;
+            (denom, None)
+        }
+        Denom::Cw20(addr) => (addr.to_string(), Some(addr)),
+    };
+
+    // a reverse auction is native only, see `validiate_kind`
+    if let Some(AuctionKind::Reverse { budget, .. }) = &msg.kind {
+        if collect_coins(funds, &denom)?.amount != *budget {
+            return Err(ContractError::BudgetNotEscrowedErr {
+                budget: coin(budget.u128(), &denom),
+            });
+        }
+    } else if !funds.is_empty() {
+        return Err(PaymentError::NonPayable {}.into());
+    }
+
+    let weights = msg.weights.unwrap_or_default();
+    exec::validiate_weights(&denom, &weights)?;
+    exec::validiate_schedule(&env.block, msg.start.as_ref(), msg.end.as_ref())?;
+    exec::validiate_extension(msg.end.as_ref(), msg.extension.as_ref())?;
+    exec::validiate_commission(&msg.commission)?;
+    exec::validiate_reserve(msg.reserve.as_ref(), msg.reserve_commitment.as_ref())?;
+    exec::validiate_buy_now(
+        msg.buy_now.as_ref(),
+        msg.reserve.as_ref(),
+        msg.reserve_commitment.as_ref(),
+        &weights,
+    )?;
+    if msg.increment_bps.is_some_and(|bps| bps > MAX_BPS) {
+        return Err(ContractError::InvalidIncrementErr {});
+    }
+
+    let owner = msg
+        .owner
+        .map(|owner| deps.api.addr_validate(&owner))
+        .transpose()?
+        .unwrap_or(sender);
+
+    let beneficiary = msg
+        .beneficiary
+        .map(|beneficiary| deps.api.addr_validate(&beneficiary))
+        .transpose()?;
+
+    let fee_collector = msg
+        .fee_collector
+        .map(|fee_collector| deps.api.addr_validate(&fee_collector))
+        .transpose()?;
+
+    let nft = msg
+        .nft
+        .map(|nft| deps.api.addr_validate(&nft))
+        .transpose()?;
+
+    let mut state = State::new(
+        owner,
         msg.name,
-        coin(msg.tick, ATOM_DENOM),
-        coin(msg.commission, ATOM_DENOM),
+        denom.clone(),
+        coin(msg.tick, &denom),
+        msg.commission,
     );
+    state.beneficiary = beneficiary;
+    state.cw20 = cw20;
+    state.weights = weights;
+    state.fee_collector = fee_collector;
+    state.start = msg.start;
+    state.end = msg.end;
+    state.extension = msg.extension;
+    state.nft = nft;
+    state.reserve = msg.reserve;
+    state.reserve_commitment = msg.reserve_commitment;
+    state.buy_now = msg.buy_now;
+    state.increment_bps = msg.increment_bps;
+    state.kind = msg.kind.unwrap_or_default();
+
+    // a Dutch auction decays from its start, now if missing
+    if let AuctionKind::Dutch { decay, .. } = &state.kind {
+        state.start = state.start.or(match decay.per() {
+            Duration::Height(_) => Some(Scheduled::AtHeight(env.block.height)),
+            Duration::Time(_) => Some(Scheduled::AtTime(env.block.time)),
+        });
+    }
+    state.status = state.status_at(&env.block);
+
+    let auction_id = AUCTION_SEQ.load(deps.storage)? + 1;
 
-    STATE.save(deps.storage, &state)?;
+    AUCTION_SEQ.save(deps.storage, &auction_id)?;
+    STATE.save(deps.storage, auction_id, &state)?;
 
-    Ok(Response::new())
+    Ok(auction_id)
 }
 
 pub mod exec {
-    use cosmwasm_std::{coin, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response};
+    use cosmwasm_std::{
+        from_json, to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Decimal,
+        Deps, DepsMut, Env, HexBinary, MessageInfo, Response, StdError, StdResult, Storage,
+        Uint128, WasmMsg,
+    };
+    use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, UncheckedDenom};
+    use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+    use cw_utils::{Duration, Expiration, Scheduled};
 
     use crate::{
-        helper::{add_coin, collect_coins},
-        msg::ExecuteMsg::{self, *},
-        state::{Bid, BidStatus, State, BIDDINGS, STATE},
-        ContractError, ATOM_DENOM,
+        helper::{add_coin, add_coins, collect_coins, validiate_denom_name},
+        msg::{
+            AuctionMsg,
+            ExecuteMsg::{self, *},
+            ReceiveMsg, ReceiveNftMsg, Reveal,
+        },
+        state::{
+            self, unit_allocations, unit_bids, AuctionItem, AuctionKind, Bid, BidStatus, BuyNow,
+            CommissionModel, Commitment, Decay, DenomWeight, Deposit, Extension, Reserve, State,
+            UnitBid, BIDDINGS, COMMITMENTS, CONFIG, MAX_BPS, STATE,
+        },
+        ContractError,
     };
 
+    use super::open_auction;
+
     pub fn execute(
         deps: DepsMut,
         env: Env,
@@ -48,150 +196,1676 @@ pub mod exec {
         msg: ExecuteMsg,
     ) -> Result<Response, ContractError> {
         match msg {
-            Bidding {} => bid(deps, info),
-            Close {} => close(deps, env, info),
-            Retract { receiver } => retract(deps, env, info, receiver),
+            Open(auction) => open(deps, env, info, *auction),
+            UpdateCreators { creators } => update_creators(deps, info, creators),
+            Bidding { auction_id } => bid(deps, env, info, auction_id),
+            Commit { auction_id, hash } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_denom(&info.funds, &state)?;
+
+                commit(
+                    deps,
+                    env,
+                    auction_id,
+                    state,
+                    &info.sender,
+                    &info.funds,
+                    hash,
+                )
+            }
+            Ask { auction_id, price } => ask(deps, env, info, auction_id, price),
+            BidUnits {
+                auction_id,
+                price,
+                quantity,
+            } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_denom(&info.funds, &state)?;
+
+                let units = (price, quantity);
+                place_unit_bid(
+                    deps,
+                    env,
+                    auction_id,
+                    state,
+                    &info.sender,
+                    &info.funds,
+                    units,
+                )
+            }
+            Buy { auction_id } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_denom(&info.funds, &state)?;
+
+                buy(deps, env, auction_id, state, &info.sender, &info.funds)
+            }
+            ExecuteMsg::Reveal {
+                auction_id,
+                amount,
+                salt,
+            } => reveal(deps, env, info, auction_id, amount, salt),
+            Close { auction_id, reveal } => close(deps, env, info, auction_id, reveal),
+            Retract {
+                auction_id,
+                receiver,
+            } => retract(deps, env, info, auction_id, receiver),
+            Pause { auction_id } => set_paused(deps, info, auction_id, true),
+            Unpause { auction_id } => set_paused(deps, info, auction_id, false),
+            Cancel { auction_id } => cancel(deps, auction_id, &info.sender, &info.funds),
+            EmergencyCancel { auction_id } => emergency_cancel(deps, info, auction_id),
+            ProposeOwner {
+                auction_id,
+                owner,
+                expiry,
+            } => propose_owner(deps, env, info, auction_id, owner, expiry),
+            AcceptOwnership { auction_id } => accept_ownership(deps, env, info, auction_id),
+            CancelOwnershipProposal { auction_id } => {
+                cancel_ownership_proposal(deps, info, auction_id)
+            }
+            RenounceOwnership { auction_id } => renounce_ownership(deps, info, auction_id),
+            Receive(msg) => receive(deps, env, info, msg),
+            ReceiveNft(msg) => receive_nft(deps, info, msg),
+        }
+    }
+
+    pub fn open(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: AuctionMsg,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let sender = info.sender;
+
+        if !config.can_open(&sender) {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let auction_id = open_auction(deps, &env, sender.clone(), &info.funds, msg)?;
+
+        let resp = Response::new()
+            .set_data(to_json_binary(&auction_id)?)
+            .add_attribute("action", "open")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn update_creators(
+        deps: DepsMut,
+        info: MessageInfo,
+        creators: Option<Vec<String>>,
+    ) -> Result<Response, ContractError> {
+        let mut config = CONFIG.load(deps.storage)?;
+
+        validiate_owner(&info.sender, Some(&config.admin))?;
+
+        config.creators = creators
+            .map(|creators| {
+                creators
+                    .iter()
+                    .map(|creator| deps.api.addr_validate(creator))
+                    .collect::<StdResult<Vec<_>>>()
+            })
+            .transpose()?;
+
+        CONFIG.save(deps.storage, &config)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_creators")
+            .add_attribute("sender", info.sender);
+
+        Ok(resp)
+    }
+
+    pub fn bid(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        auction_id: u64,
+    ) -> Result<Response, ContractError> {
+        let state = STATE.load(deps.storage, auction_id)?;
+        validiate_denom(&info.funds, &state)?;
+
+        place_bid(deps, env, auction_id, state, &info.sender, &info.funds)
+    }
+
+    // The cw20 contract calls this with the amount sent by the bidder
+    pub fn receive(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw20ReceiveMsg,
+    ) -> Result<Response, ContractError> {
+        let sender = deps.api.addr_validate(&msg.sender)?;
+        let funds = [Coin::new(msg.amount.u128(), &info.sender)];
+
+        match from_json(&msg.msg)? {
+            ReceiveMsg::Bid { auction_id } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_cw20(&info.sender, &state)?;
+
+                place_bid(deps, env, auction_id, state, &sender, &funds)
+            }
+            ReceiveMsg::BidUnits {
+                auction_id,
+                price,
+                quantity,
+            } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_cw20(&info.sender, &state)?;
+
+                let units = (price, quantity);
+                place_unit_bid(deps, env, auction_id, state, &sender, &funds, units)
+            }
+            ReceiveMsg::Buy { auction_id } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_cw20(&info.sender, &state)?;
+
+                buy(deps, env, auction_id, state, &sender, &funds)
+            }
+            ReceiveMsg::Commit { auction_id, hash } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_cw20(&info.sender, &state)?;
+
+                commit(deps, env, auction_id, state, &sender, &funds, hash)
+            }
+            ReceiveMsg::Cancel { auction_id } => {
+                let state = STATE.load(deps.storage, auction_id)?;
+                validiate_cw20(&info.sender, &state)?;
+
+                cancel(deps, auction_id, &sender, &funds)
+            }
+        }
+    }
+
+    /// Bid a unit price for a quantity of a multi-unit auction, the deposit after the
+    /// commission must cover both
+    pub fn place_unit_bid(
+        deps: DepsMut,
+        env: Env,
+        auction_id: u64,
+        mut state: State,
+        sender: &Addr,
+        funds: &[Coin],
+        (price, quantity): (Uint128, u64),
+    ) -> Result<Response, ContractError> {
+        let AuctionKind::MultiUnit { quantity: max } = state.kind else {
+            return Err(ContractError::WrongAuctionKindErr {});
+        };
+
+        can_bid(sender, state.owner.as_ref())?;
+        validiate_opening(&state, &env.block)?;
+
+        if quantity == 0 || quantity > max {
+            return Err(ContractError::InvalidUnitsErr { max });
+        }
+
+        if unit_bids().has(deps.storage, (auction_id, sender.clone())) {
+            return Err(ContractError::BidAlreadyCommittedErr {});
+        }
+
+        let spread = state.normalize(funds)?;
+        let fee = state.commission.commission(spread.amount);
+
+        validiate_bid(&state, &spread, fee)?;
+
+        let (net, commission) = split_commission(&state, funds, fee)?;
+
+        let total = price
+            .checked_mul(Uint128::from(quantity))
+            .map_err(StdError::from)?;
+        let net_spread = state.normalize(&net)?;
+        if price < state.tick.amount || net_spread.amount < total {
+            return Err(ContractError::InvalidBidErr {
+                total_bid: net_spread,
+            });
+        }
+
+        state.unit_seq += 1;
+        state.status = BidStatus::Opening {};
+        add_coins(&mut state.collected, &commission);
+
+        let bid = UnitBid {
+            auction_id,
+            bidder: sender.clone(),
+            price,
+            quantity,
+            seq: state.unit_seq,
+            allocated: 0,
+        };
+        let deposit = Deposit {
+            bid: net,
+            commission: commission.clone(),
+        };
+
+        STATE.save(deps.storage, auction_id, &state)?;
+        BIDDINGS.save(deps.storage, (auction_id, sender.clone()), &deposit)?;
+        unit_bids().save(deps.storage, (auction_id, sender.clone()), &bid)?;
+
+        let mut resp = Response::new();
+
+        if let Some(fee_collector) = state.fee_collector() {
+            resp = resp.add_messages(send_coins(&state, fee_collector, commission)?);
+        }
+
+        resp = resp
+            .add_attribute("action", "bid_units")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("price", price.to_string())
+            .add_attribute("quantity", quantity.to_string());
+
+        Ok(resp)
+    }
+
+    /// Undercut the lowest ask of a reverse auction, the bond is posted with the first ask
+    pub fn ask(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        auction_id: u64,
+        price: Uint128,
+    ) -> Result<Response, ContractError> {
+        let sender = info.sender;
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        let AuctionKind::Reverse { budget, bond } = state.kind else {
+            return Err(ContractError::WrongAuctionKindErr {});
+        };
+
+        can_bid(&sender, state.owner.as_ref())?;
+        validiate_opening(&state, &env.block)?;
+
+        let mut deposit = BIDDINGS
+            .may_load(deps.storage, (auction_id, sender.clone()))?
+            .unwrap_or_default();
+
+        let required = match deposit.bid.is_empty() {
+            true => bond,
+            false => Uint128::zero(),
+        };
+        let paid = collect_coins(&info.funds, &state.denom)?;
+        if paid.amount != required {
+            return Err(ContractError::InvalidBondErr {
+                bond: Coin::new(required.u128(), &state.denom),
+            });
+        }
+
+        if price.is_zero() {
+            return Err(ContractError::InvalidAskErr {});
+        }
+
+        // each ask undercuts the lowest by the tick, the first one fits the budget,
+        // none can once the lowest is within a tick of zero
+        let maximum = match &state.highest {
+            Some(lowest) => lowest.bid.amount.checked_sub(state.tick.amount).ok(),
+            None => Some(budget),
+        };
+        let maximum = maximum.unwrap_or_default();
+        if maximum.is_zero()
+            || price > maximum
+            || state
+                .highest
+                .as_ref()
+                .is_some_and(|lowest| price >= lowest.bid.amount)
+        {
+            return Err(ContractError::AskTooHighErr {
+                price: Coin::new(price.u128(), &state.denom),
+                maximum: Coin::new(maximum.u128(), &state.denom),
+            });
+        }
+
+        let lowest = Bid {
+            bid: Coin::new(price.u128(), &state.denom),
+            bidder: sender.clone(),
+        };
+        if let Some(undercut) = state.highest.take().filter(|b| b.bidder != sender) {
+            state.runner_up = Some(undercut);
+        }
+        state.highest = Some(lowest);
+        state.status = BidStatus::Opening {};
+        let extended = state.extend_end(&env.block);
+
+        add_coins(&mut deposit.bid, &[paid]);
+
+        STATE.save(deps.storage, auction_id, &state)?;
+        BIDDINGS.save(deps.storage, (auction_id, sender.clone()), &deposit)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "ask")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("price", price.to_string());
+
+        if let Some(end) = extended {
+            resp = resp.add_attribute("end", end.seconds().to_string());
+        }
+
+        Ok(resp)
+    }
+
+    /// Buy a Dutch auction at the current price, the commission is taken from the price
+    /// and the change refunded
+    pub fn buy(
+        deps: DepsMut,
+        env: Env,
+        auction_id: u64,
+        mut state: State,
+        sender: &Addr,
+        funds: &[Coin],
+    ) -> Result<Response, ContractError> {
+        let Some(price) = state.current_price(&env.block) else {
+            return Err(ContractError::WrongAuctionKindErr {});
+        };
+
+        can_bid(sender, state.owner.as_ref())?;
+        validiate_opening(&state, &env.block)?;
+
+        let paid = collect_coins(funds, &state.denom)?;
+        let price = Coin::new(price.u128(), &state.denom);
+
+        if paid.amount < price.amount {
+            return Err(ContractError::BidTooLowErr {
+                total: paid,
+                minimum: price,
+            });
+        }
+
+        let fee = state.commission.commission(price.amount).min(price.amount);
+        let commission = Coin::new(fee.u128(), &state.denom);
+        let proceeds = Coin::new((price.amount - fee).u128(), &state.denom);
+        let change = Coin::new((paid.amount - price.amount).u128(), &state.denom);
+
+        state.status = BidStatus::Closed {};
+        state.highest = Some(Bid {
+            bid: price.clone(),
+            bidder: sender.clone(),
+        });
+        state.winner = Some(sender.clone());
+        state.clearing_price = Some(price.clone());
+        add_coins(&mut state.collected, std::slice::from_ref(&commission));
+
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let mut resp = Response::new().add_messages(send_coins(&state, sender, vec![change])?);
+
+        if let Some(fee_collector) = state.fee_collector() {
+            resp = resp.add_messages(send_coins(&state, fee_collector, vec![commission])?);
+        }
+
+        if let Some(beneficiary) = state.beneficiary() {
+            resp = resp.add_messages(send_coins(&state, beneficiary, vec![proceeds])?);
+        }
+
+        if let Some(item) = &state.item {
+            resp = resp.add_message(transfer_nft(item, sender)?);
+        }
+
+        resp = resp
+            .add_attribute("action", "buy")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("price", price.amount.to_string());
+
+        Ok(resp)
+    }
+
+    /// Seal a bid with a deposit, the commission is taken from the deposit like a bid
+    pub fn commit(
+        deps: DepsMut,
+        env: Env,
+        auction_id: u64,
+        mut state: State,
+        sender: &Addr,
+        funds: &[Coin],
+        hash: HexBinary,
+    ) -> Result<Response, ContractError> {
+        if !state.is_sealed() {
+            return Err(ContractError::WrongAuctionKindErr {});
+        }
+
+        can_bid(sender, state.owner.as_ref())?;
+        validiate_opening(&state, &env.block)?;
+
+        if hash.len() != 32 {
+            return Err(ContractError::InvalidCommitmentErr {});
+        }
+
+        if COMMITMENTS.has(deps.storage, (auction_id, sender.clone())) {
+            return Err(ContractError::BidAlreadyCommittedErr {});
+        }
+
+        let spread = state.normalize(funds)?;
+        let fee = state.commission.commission(spread.amount);
+
+        validiate_bid(&state, &spread, fee)?;
+
+        let (net, commission) = split_commission(&state, funds, fee)?;
+
+        state.status = BidStatus::Opening {};
+        add_coins(&mut state.collected, &commission);
+
+        let deposit = Deposit {
+            bid: net,
+            commission: commission.clone(),
+        };
+        let commitment = Commitment {
+            hash,
+            revealed: false,
+        };
+
+        STATE.save(deps.storage, auction_id, &state)?;
+        BIDDINGS.save(deps.storage, (auction_id, sender.clone()), &deposit)?;
+        COMMITMENTS.save(deps.storage, (auction_id, sender.clone()), &commitment)?;
+
+        let mut resp = Response::new();
+
+        if let Some(fee_collector) = state.fee_collector() {
+            resp = resp.add_messages(send_coins(&state, fee_collector, commission)?);
+        }
+
+        resp = resp
+            .add_attribute("action", "commit")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("commission", fee.to_string());
+
+        Ok(resp)
+    }
+
+    /// Open a sealed bid, the highest reveal becomes the highest bid
+    pub fn reveal(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        auction_id: u64,
+        amount: Uint128,
+        salt: Binary,
+    ) -> Result<Response, ContractError> {
+        let sender = info.sender;
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        can_reveal(&state, &env.block)?;
+
+        let mut commitment = COMMITMENTS
+            .may_load(deps.storage, (auction_id, sender.clone()))?
+            .filter(|commitment| !commitment.revealed)
+            .ok_or(ContractError::Unauthorized {})?;
+
+        if state::commitment(amount, &salt) != commitment.hash {
+            return Err(ContractError::InvalidBidRevealErr {});
+        }
+
+        // the deposit must cover the revealed amount
+        let deposit = BIDDINGS.load(deps.storage, (auction_id, sender.clone()))?;
+        let total = state.normalize(&deposit.bid)?;
+        if amount < state.tick.amount || amount > total.amount {
+            return Err(ContractError::InvalidBidErr {
+                total_bid: Coin::new(amount.u128(), &state.denom),
+            });
+        }
+
+        commitment.revealed = true;
+        COMMITMENTS.save(deps.storage, (auction_id, sender.clone()), &commitment)?;
+
+        // keep the top two reveals, the first reveal wins a tie
+        let revealed = Bid {
+            bid: Coin::new(amount.u128(), &state.denom),
+            bidder: sender.clone(),
+        };
+        let above = |bid: &Option<Bid>| bid.as_ref().is_none_or(|bid| amount > bid.bid.amount);
+        if above(&state.highest) {
+            state.runner_up = state.highest.replace(revealed);
+        } else if above(&state.runner_up) {
+            state.runner_up = Some(revealed);
+        }
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "reveal")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("amount", amount.to_string());
+
+        Ok(resp)
+    }
+
+    // The owner sends the auctioned nft to open the bid
+    pub fn receive_nft(
+        deps: DepsMut,
+        info: MessageInfo,
+        msg: Cw721ReceiveMsg,
+    ) -> Result<Response, ContractError> {
+        let ReceiveNftMsg::Deposit { auction_id } = from_json(&msg.msg)?;
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        if state.nft.as_ref() != Some(&info.sender)
+            || !state.is_owner(&Addr::unchecked(&msg.sender))
+        {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if state.status.is_closed() {
+            return Err(ContractError::BidAlreadyClosed {});
+        }
+
+        if state.item.is_some() {
+            return Err(ContractError::NftAlreadyDepositedErr {});
         }
+
+        state.item = Some(AuctionItem {
+            contract: info.sender,
+            token_id: msg.token_id.clone(),
+        });
+
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "receive_nft")
+            .add_attribute("sender", msg.sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("token_id", msg.token_id);
+
+        Ok(resp)
     }
 
-    pub fn bid(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-        let sender = &info.sender;
+    pub fn place_bid(
+        deps: DepsMut,
+        env: Env,
+        auction_id: u64,
+        mut state: State,
+        sender: &Addr,
+        funds: &[Coin],
+    ) -> Result<Response, ContractError> {
+        if !state.kind.accepts_bids() {
+            return Err(ContractError::WrongAuctionKindErr {});
+        }
+
+        can_bid(sender, state.owner.as_ref())?;
+        validiate_opening(&state, &env.block)?;
+
+        let spread = state.normalize(funds)?;
+
+        let fee = state.commission.commission(spread.amount);
+
+        validiate_bid(&state, &spread, fee)?;
+
+        // the commission of this bid goes to the fee collector right away
+        let (net, commission) = split_commission(&state, funds, fee)?;
+
+        // Update the state if the bidding is valid
+        let mut deposit = BIDDINGS
+            .may_load(deps.storage, (auction_id, sender.clone()))?
+            .unwrap_or_default();
+        let bid = if deposit.bid.is_empty() {
+            None
+        } else {
+            Some(state.normalize(&deposit.bid)?)
+        };
+
+        let net_spread = state.normalize(&net)?;
+
+        update_state(&mut state, sender, bid, &net_spread)?;
+        state.status = BidStatus::Opening {};
+        let extended = state
+            .extend_end(&env.block)
+            .or_else(|| state.reset_countdown(&env.block));
+
+        // reaching the buy now price sells right away, regardless of the reserve
+        let bought = state.is_bought_now();
+        if bought {
+            state.status = BidStatus::Closed {};
+            state.winner = Some(sender.clone());
+            state.clearing_price = state
+                .buy_now
+                .as_ref()
+                .map(|buy_now| Coin::new(buy_now.price.u128(), &state.denom));
+        } else if state.is_buy_now_expired() {
+            state.buy_now = None;
+        }
+
+        add_coins(&mut deposit.bid, &net);
+        add_coins(&mut deposit.commission, &commission);
+        add_coins(&mut state.collected, &commission);
+
+        // save the state and bids
+        STATE.save(deps.storage, auction_id, &state)?;
+        BIDDINGS.save(deps.storage, (auction_id, sender.clone()), &deposit)?;
+
+        // the winner pays the buy now price, the rest of its deposit is retractable
+        if let (true, Some(price)) = (bought, &state.clearing_price) {
+            charge_deposit(deps.storage, auction_id, sender, price.amount)?;
+        }
+
+        let mut resp = Response::new();
+
+        if let Some(fee_collector) = state.fee_collector() {
+            resp = resp.add_messages(send_coins(&state, fee_collector, commission)?);
+        }
+
+        if bought {
+            resp = resp
+                .add_messages(settle(deps.as_ref(), &env, &state, auction_id)?)
+                .add_attribute("buy_now", "true");
+        }
+
+        resp = resp
+            .add_attribute("action", "bid")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("spread", spread.amount.to_string())
+            .add_attribute("commission", fee.to_string());
+
+        if let Some(end) = extended {
+            resp = resp.add_attribute("end", end.seconds().to_string());
+        }
+
+        Ok(resp)
+    }
+
+    pub fn close(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        auction_id: u64,
+        reveal: Option<Reveal>,
+    ) -> Result<Response, ContractError> {
+        let sender = info.sender;
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        can_close(&state, &sender, &env.block)?;
+
+        reveal_reserve(&mut state, reveal, &env.block)?;
+
+        state.status = BidStatus::Closed {};
+        state.paused = false;
+
+        let msgs = match state.kind {
+            AuctionKind::MultiUnit { quantity } => {
+                settle_units(deps.branch(), &env, &mut state, auction_id, quantity)?
+            }
+            _ => {
+                // no sale below the reserve, every bidder can retract
+                state.winner = state
+                    .highest
+                    .as_ref()
+                    .filter(|_| state.is_reserve_met())
+                    .map(|bid| bid.bidder.clone());
+                state.clearing_price = state.winner.as_ref().and_then(|_| state.winning_price());
+
+                STATE.save(deps.storage, auction_id, &state)?;
+
+                // the winner pays the clearing price, the rest of its deposit is retractable
+                if let (true, Some(winner), Some(price)) = (
+                    state.kind.pays_clearing_price(),
+                    &state.winner,
+                    &state.clearing_price,
+                ) {
+                    charge_deposit(deps.storage, auction_id, winner, price.amount)?;
+                }
+
+                settle(deps.as_ref(), &env, &state, auction_id)?
+            }
+        };
+
+        let resp = Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "close")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        Ok(resp)
+    }
+
+    /// Pay the winner's deposit to the beneficiary and deliver the nft to the winner,
+    /// or back to the owner without a winner
+    pub fn settle(
+        deps: Deps,
+        env: &Env,
+        state: &State,
+        auction_id: u64,
+    ) -> Result<Vec<CosmosMsg>, ContractError> {
+        if let AuctionKind::Reverse { budget, .. } = state.kind {
+            return settle_reverse(deps, env, state, budget);
+        }
+
+        // the whole bidding of the winner, in the denoms it was deposited
+        let highest_coin = match (&state.winner, &state.clearing_price) {
+            (Some(_), Some(price)) if state.pays_clearing_price() => vec![price.clone()],
+            (Some(winner), _) => {
+                BIDDINGS
+                    .may_load(deps.storage, (auction_id, winner.clone()))?
+                    .unwrap_or_default()
+                    .bid
+            }
+            _ => vec![],
+        };
+
+        let contract_balances = query_balances(deps, state, &env.contract.address)?;
+
+        validiate_balances(&contract_balances, &highest_coin)?;
+
+        let mut msgs = vec![];
+
+        if let Some(beneficiary) = state.beneficiary() {
+            msgs.extend(send_coins(state, beneficiary, highest_coin)?);
+        }
+
+        let recipient = state
+            .winner
+            .as_ref()
+            .or(state.owner.as_ref())
+            .or(state.beneficiary());
+        if let (Some(item), Some(recipient)) = (&state.item, recipient) {
+            msgs.push(transfer_nft(item, recipient)?);
+        }
+
+        Ok(msgs)
+    }
+
+    /// Take the amount out of the deposit of a bidder, in the bidding denom
+    pub fn charge_deposit(
+        storage: &mut dyn Storage,
+        auction_id: u64,
+        bidder: &Addr,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        BIDDINGS.update(storage, (auction_id, bidder.clone()), |deposit| {
+            let mut deposit = deposit.unwrap_or_default();
+            for c in deposit.bid.iter_mut() {
+                c.amount = c.amount.checked_sub(amount)?;
+            }
+            deposit.bid.retain(|c| !c.amount.is_zero());
+
+            Ok::<_, StdError>(deposit)
+        })?;
+
+        Ok(())
+    }
+
+    /// Charge every allocated bidder the clearing price per unit and pay the beneficiary,
+    /// the rest of the deposits is retractable
+    pub fn settle_units(
+        deps: DepsMut,
+        env: &Env,
+        state: &mut State,
+        auction_id: u64,
+        quantity: u64,
+    ) -> Result<Vec<CosmosMsg>, ContractError> {
+        let (allocations, price) = unit_allocations(deps.storage, auction_id, quantity)?;
+
+        let mut proceeds = Uint128::zero();
+
+        if let Some(price) = price {
+            for (bidder, units) in allocations {
+                let charge = price * Uint128::from(units);
+                charge_deposit(deps.storage, auction_id, &bidder, charge)?;
+
+                unit_bids().update(deps.storage, (auction_id, bidder), |bid| {
+                    let mut bid = bid.ok_or_else(|| StdError::not_found("UnitBid"))?;
+                    bid.allocated = units;
+                    Ok::<_, StdError>(bid)
+                })?;
+
+                proceeds += charge;
+            }
+        }
+
+        state.clearing_price = price.map(|price| Coin::new(price.u128(), &state.denom));
+        STATE.save(deps.storage, auction_id, state)?;
+
+        let proceeds = vec![Coin::new(proceeds.u128(), &state.denom)];
+
+        let contract_balances = query_balances(deps.as_ref(), state, &env.contract.address)?;
+        validiate_balances(&contract_balances, &proceeds)?;
+
+        match state.beneficiary() {
+            Some(beneficiary) => Ok(send_coins(state, beneficiary, proceeds)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Pay the winning ask from the budget, less the commission, and the rest of the
+    /// budget back to the buyer. Every supplier retracts its bond
+    pub fn settle_reverse(
+        deps: Deps,
+        env: &Env,
+        state: &State,
+        budget: Uint128,
+    ) -> Result<Vec<CosmosMsg>, ContractError> {
+        let ask = match &state.winner {
+            Some(_) => state
+                .clearing_price
+                .as_ref()
+                .map(|price| price.amount)
+                .unwrap_or_default(),
+            None => Uint128::zero(),
+        };
+        let fee = state.commission.commission(ask).min(ask);
+
+        let contract_balances = query_balances(deps, state, &env.contract.address)?;
+        validiate_balances(
+            &contract_balances,
+            &[Coin::new(budget.u128(), &state.denom)],
+        )?;
+
+        let coins = |amount: Uint128| vec![Coin::new(amount.u128(), &state.denom)];
+        let mut msgs = vec![];
+
+        if let Some(winner) = &state.winner {
+            msgs.extend(send_coins(state, winner, coins(ask - fee))?);
+        }
+
+        if let Some(fee_collector) = state.fee_collector() {
+            msgs.extend(send_coins(state, fee_collector, coins(fee))?);
+        }
+
+        if let Some(buyer) = state.owner.as_ref().or(state.beneficiary()) {
+            msgs.extend(send_coins(state, buyer, coins(budget - ask))?);
+        }
+
+        Ok(msgs)
+    }
+
+    pub fn retract(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        auction_id: u64,
+        receiver: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let sender = info.sender;
+
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        can_retract(&state, &sender)?;
+
+        can_bid(&sender, state.owner.as_ref())?;
+
+        let receiver = &receiver
+            .as_ref()
+            .map(Addr::unchecked)
+            .unwrap_or(sender.clone());
+        let bid = BIDDINGS.may_load(deps.storage, (auction_id, sender.clone()))?;
+
+        let resp = if let Some(deposit) = bid {
+            if deposit.bid.is_empty() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            // retract only once
+            BIDDINGS.remove(deps.storage, (auction_id, sender.clone()));
+
+            // the commission was already paid on each bid, unless the bid was cancelled,
+            // the fees of a penny auction are never refunded
+            let mut bids = deposit.bid;
+            if state.status.is_cancelled() && state.kind.refunds_commission() {
+                add_coins(&mut bids, &deposit.commission);
+            }
+
+            let contract_balances = query_balances(deps.as_ref(), &state, &env.contract.address)?;
+            validiate_balances(&contract_balances, &bids)?;
+
+            let mut resp = Response::new();
+
+            // a sealed bid never revealed forfeits part of its deposit to the beneficiary
+            if let AuctionKind::SealedBid { forfeit_bps, .. } = state.kind {
+                let revealed = COMMITMENTS
+                    .may_load(deps.storage, (auction_id, sender.clone()))?
+                    .is_none_or(|commitment| commitment.revealed);
+
+                if !revealed && !state.emergency && !state.status.is_cancelled() {
+                    let forfeit = bids
+                        .iter_mut()
+                        .map(|c| {
+                            let forfeit = c.amount.multiply_ratio(forfeit_bps, MAX_BPS);
+                            c.amount -= forfeit;
+                            Coin::new(forfeit.u128(), &c.denom)
+                        })
+                        .collect();
+
+                    if let Some(beneficiary) = state.beneficiary() {
+                        resp = resp.add_messages(send_coins(&state, beneficiary, forfeit)?);
+                    }
+                }
+            }
+
+            resp.add_messages(send_coins(&state, receiver, bids)?)
+        } else {
+            Response::new()
+        }
+        .add_attribute("action", "retract")
+        .add_attribute("sender", sender)
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("receiver", receiver);
+
+        Ok(resp)
+    }
+
+    pub fn set_paused(
+        deps: DepsMut,
+        info: MessageInfo,
+        auction_id: u64,
+        paused: bool,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        validiate_owner(&info.sender, state.owner.as_ref())?;
+
+        if state.status.is_closed() {
+            return Err(ContractError::BidAlreadyClosed {});
+        }
+
+        state.paused = paused;
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", if paused { "pause" } else { "unpause" })
+            .add_attribute("sender", info.sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn cancel(
+        deps: DepsMut,
+        auction_id: u64,
+        sender: &Addr,
+        funds: &[Coin],
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        validiate_owner(sender, state.owner.as_ref())?;
+
+        if state.status.is_closed() {
+            return Err(ContractError::BidAlreadyClosed {});
+        }
+
+        // the commission is refunded to the bidders as well
+        let required = match state.kind.refunds_commission() {
+            true => state.collected.clone(),
+            false => vec![],
+        };
+        if sorted_coins(funds) != sorted_coins(&required) {
+            return Err(ContractError::CommissionRefundErr { required });
+        }
+
+        state.status = BidStatus::Cancelled {};
+        state.winner = None;
+        state.paused = false;
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let mut resp = Response::new()
+            .add_messages(refund_budget(&state, sender)?)
+            .add_attribute("action", "cancel")
+            .add_attribute("sender", sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        // the nft goes back to the owner
+        if let Some(item) = &state.item {
+            resp = resp.add_message(transfer_nft(item, sender)?);
+        }
+
+        Ok(resp)
+    }
+
+    fn sorted_coins(coins: &[Coin]) -> Vec<Coin> {
+        let mut sorted = vec![];
+        add_coins(&mut sorted, coins);
+        sorted.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+        sorted
+    }
+
+    pub fn emergency_cancel(
+        deps: DepsMut,
+        info: MessageInfo,
+        auction_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        validiate_owner(&info.sender, state.owner.as_ref())?;
+
+        if state.status.is_closed() {
+            return Err(ContractError::BidAlreadyClosed {});
+        }
+
+        // no winner, so the highest bidder can retract as well
+        state.status = BidStatus::Closed {};
+        state.winner = None;
+        state.paused = false;
+        state.emergency = true;
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let mut resp = Response::new()
+            .add_messages(refund_budget(&state, &info.sender)?)
+            .add_attribute("action", "emergency_cancel")
+            .add_attribute("sender", &info.sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        // the nft goes back to the owner
+        if let Some(item) = &state.item {
+            resp = resp.add_message(transfer_nft(item, &info.sender)?);
+        }
+
+        Ok(resp)
+    }
+
+    /// The escrowed budget of a reverse auction goes back to the buyer
+    pub fn refund_budget(state: &State, buyer: &Addr) -> StdResult<Vec<CosmosMsg>> {
+        match state.kind {
+            AuctionKind::Reverse { budget, .. } => {
+                send_coins(state, buyer, vec![Coin::new(budget.u128(), &state.denom)])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    pub fn propose_owner(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        auction_id: u64,
+        owner: String,
+        expiry: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        validiate_owner(&info.sender, state.owner.as_ref())?;
+
+        if expiry.is_some_and(|expiry| expiry.is_expired(&env.block)) {
+            return Err(ContractError::OwnershipProposalExpiredErr {});
+        }
+
+        let owner = deps.api.addr_validate(&owner)?;
+
+        state.pending_owner = Some(owner.clone());
+        state.pending_expiry = expiry;
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "propose_owner")
+            .add_attribute("sender", info.sender)
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("pending_owner", owner);
+
+        Ok(resp)
+    }
+
+    pub fn accept_ownership(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        auction_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        let Some(pending_owner) = state.pending_owner.take() else {
+            return Err(ContractError::NoOwnershipProposalErr {});
+        };
+
+        validiate_owner(&info.sender, Some(&pending_owner))?;
+
+        if state
+            .pending_expiry
+            .take()
+            .is_some_and(|expiry| expiry.is_expired(&env.block))
+        {
+            return Err(ContractError::OwnershipProposalExpiredErr {});
+        }
+
+        // a bidder can't become the owner
+        if BIDDINGS.has(deps.storage, (auction_id, pending_owner.clone())) {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        state.owner = Some(pending_owner);
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "accept_ownership")
+            .add_attribute("sender", info.sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn cancel_ownership_proposal(
+        deps: DepsMut,
+        info: MessageInfo,
+        auction_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        validiate_owner(&info.sender, state.owner.as_ref())?;
+
+        if state.pending_owner.is_none() {
+            return Err(ContractError::NoOwnershipProposalErr {});
+        }
+
+        state.pending_owner = None;
+        state.pending_expiry = None;
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "cancel_ownership_proposal")
+            .add_attribute("sender", info.sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn renounce_ownership(
+        deps: DepsMut,
+        info: MessageInfo,
+        auction_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage, auction_id)?;
+
+        validiate_owner(&info.sender, state.owner.as_ref())?;
+
+        // without an owner only the end lets the bid be closed
+        if state.end.is_none() && !state.status.is_closed() {
+            return Err(ContractError::RenounceWithoutEndErr {});
+        }
+
+        // keep paying the current beneficiary and fee collector
+        state.beneficiary = state.beneficiary().cloned();
+        state.fee_collector = state.fee_collector().cloned();
+        state.owner = None;
+        state.pending_owner = None;
+        state.pending_expiry = None;
+        // nobody could unpause it anymore
+        state.paused = false;
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "renounce_ownership")
+            .add_attribute("sender", info.sender)
+            .add_attribute("auction_id", auction_id.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn validiate_bid(state: &State, spread: &Coin, fee: Uint128) -> Result<(), ContractError> {
+        if spread.amount < state.tick.amount || spread.amount < fee {
+            return Err(ContractError::InvalidBidErr {
+                total_bid: spread.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn validiate_reserve(
+        reserve: Option<&Reserve>,
+        commitment: Option<&HexBinary>,
+    ) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidReserveErr {
+            reason: reason.into(),
+        };
+
+        match (reserve, commitment) {
+            (Some(_), Some(_)) => Err(invalid("set either a reserve or a commitment")),
+            (_, Some(commitment)) if commitment.len() != 32 => {
+                Err(invalid("the commitment must be a sha256 hash"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn validiate_kind(msg: &AuctionMsg) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidAuctionKindErr {
+            reason: reason.into(),
+        };
+
+        let kind = msg.kind.clone().unwrap_or_default();
+
+        // the winner is charged part of its deposit, which must be in the bidding denom
+        if kind.pays_clearing_price() {
+            if msg
+                .weights
+                .as_ref()
+                .is_some_and(|weights| !weights.is_empty())
+            {
+                return Err(invalid("the kind accepts the bidding denom only"));
+            }
+
+            if msg.buy_now.is_some() {
+                return Err(invalid("the kind has no buy now"));
+            }
+        }
+
+        if let AuctionKind::SealedBid {
+            reveal_end,
+            forfeit_bps,
+        } = &kind
+        {
+            let reveal_after_end = match (&msg.end, reveal_end) {
+                (Some(Expiration::AtHeight(end)), Expiration::AtHeight(reveal_end)) => {
+                    end < reveal_end
+                }
+                (Some(Expiration::AtTime(end)), Expiration::AtTime(reveal_end)) => end < reveal_end,
+                _ => false,
+            };
+
+            if !reveal_after_end {
+                return Err(invalid("reveal_end must be after end"));
+            }
+
+            if *forfeit_bps > MAX_BPS {
+                return Err(invalid("forfeit_bps must not exceed 10000"));
+            }
+
+            if msg.extension.is_some() {
+                return Err(invalid("a sealed bid has no extension"));
+            }
+        }
+
+        if let AuctionKind::Penny { countdown } = &kind {
+            if *countdown == 0 {
+                return Err(invalid("the countdown must not be zero"));
+            }
+
+            if matches!(msg.end, Some(Expiration::AtHeight(_))) {
+                return Err(invalid("the countdown requires the end to be a time"));
+            }
+
+            if msg.extension.is_some() || msg.increment_bps.is_some() || msg.buy_now.is_some() {
+                return Err(invalid(
+                    "the countdown replaces the extension, increment and buy now",
+                ));
+            }
+        }
+
+        if let AuctionKind::MultiUnit { quantity } = &kind {
+            if *quantity == 0 {
+                return Err(invalid("the quantity must not be zero"));
+            }
+
+            if msg
+                .weights
+                .as_ref()
+                .is_some_and(|weights| !weights.is_empty())
+            {
+                return Err(invalid("the kind accepts the bidding denom only"));
+            }
+
+            if msg.buy_now.is_some()
+                || msg.reserve.is_some()
+                || msg.reserve_commitment.is_some()
+                || msg.nft.is_some()
+            {
+                return Err(invalid(
+                    "a multi-unit auction has no buy now, reserve or nft",
+                ));
+            }
+
+            if msg.extension.is_some() || msg.increment_bps.is_some() {
+                return Err(invalid(
+                    "a multi-unit auction has no extension or increment",
+                ));
+            }
+        }
+
+        if let AuctionKind::Reverse { budget, .. } = &kind {
+            if budget.is_zero() {
+                return Err(invalid("the budget must not be zero"));
+            }
+
+            if matches!(msg.denom, UncheckedDenom::Cw20(_)) {
+                return Err(invalid("a reverse auction escrows a native budget"));
+            }
+
+            if msg
+                .weights
+                .as_ref()
+                .is_some_and(|weights| !weights.is_empty())
+            {
+                return Err(invalid("the kind accepts the bidding denom only"));
+            }
+
+            if msg.buy_now.is_some()
+                || msg.reserve.is_some()
+                || msg.reserve_commitment.is_some()
+                || msg.nft.is_some()
+                || msg.increment_bps.is_some()
+            {
+                return Err(invalid(
+                    "a reverse auction has no buy now, reserve, nft or increment",
+                ));
+            }
+        }
+
+        if let AuctionKind::Dutch {
+            start_price,
+            floor_price,
+            decay,
+        } = &kind
+        {
+            if start_price <= floor_price {
+                return Err(invalid("start_price must be above floor_price"));
+            }
+
+            let (Decay::Linear { amount, per } | Decay::Step { amount, per }) = decay;
+            if amount.is_zero() || matches!(per, Duration::Height(0) | Duration::Time(0)) {
+                return Err(invalid("the decay must not be zero"));
+            }
+
+            let start_matches = matches!(
+                (&msg.start, per),
+                (None, _)
+                    | (Some(Scheduled::AtHeight(_)), Duration::Height(_))
+                    | (Some(Scheduled::AtTime(_)), Duration::Time(_))
+            );
+            if !start_matches {
+                return Err(invalid("start and the decay must both be heights or times"));
+            }
+
+            if msg
+                .weights
+                .as_ref()
+                .is_some_and(|weights| !weights.is_empty())
+            {
+                return Err(invalid("the kind accepts the bidding denom only"));
+            }
+
+            if msg.buy_now.is_some()
+                || msg.reserve.is_some()
+                || msg.reserve_commitment.is_some()
+                || msg.extension.is_some()
+                || msg.increment_bps.is_some()
+            {
+                return Err(invalid(
+                    "a dutch auction has no buy now, reserve, extension or increment",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validiate_buy_now(
+        buy_now: Option<&BuyNow>,
+        reserve: Option<&Reserve>,
+        reserve_commitment: Option<&HexBinary>,
+        weights: &[DenomWeight],
+    ) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidBuyNowErr {
+            reason: reason.into(),
+        };
+
+        let Some(buy_now) = buy_now else {
+            return Ok(());
+        };
+
+        if buy_now.price.is_zero() {
+            return Err(invalid("the price must not be zero"));
+        }
+
+        // the winner is charged the price out of its deposit, in the bidding denom
+        if !weights.is_empty() {
+            return Err(invalid("buy now accepts the bidding denom only"));
+        }
+
+        // a hidden reserve is only revealed on close, buying now would skip it
+        if reserve_commitment.is_some() {
+            return Err(invalid("buy now can't be set with a reserve commitment"));
+        }
+
+        if reserve.is_some_and(|reserve| reserve.price > buy_now.price) {
+            return Err(invalid("the price must not be below the reserve"));
+        }
+
+        if buy_now
+            .expire_ratio
+            .is_some_and(|ratio| ratio.is_zero() || ratio > Decimal::one())
+        {
+            return Err(invalid("the expire ratio must be within (0, 1]"));
+        }
 
-        let funds = &info.funds;
-        validiate_denom(funds, ATOM_DENOM)?;
+        Ok(())
+    }
 
-        let mut state = STATE.load(deps.storage)?;
-        can_bid(sender, &state.owner)?;
+    pub fn validiate_commission(commission: &CommissionModel) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidCommissionErr {
+            reason: reason.into(),
+        };
 
-        // Update the state if the bidding is valid
-        let bid = BIDDINGS.may_load(deps.storage, sender.clone())?;
+        match commission {
+            CommissionModel::Flat { .. } => {}
+            CommissionModel::Percentage { bps, min, max } => {
+                if *bps > MAX_BPS {
+                    return Err(invalid("bps must not exceed 10000"));
+                }
+
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(invalid("min must not exceed max"));
+                    }
+                }
+            }
+            CommissionModel::Tiered { tiers } => {
+                if tiers.is_empty() {
+                    return Err(invalid("tiers must not be empty"));
+                }
+
+                if tiers.iter().any(|tier| tier.bps > MAX_BPS) {
+                    return Err(invalid("bps must not exceed 10000"));
+                }
+
+                if tiers.windows(2).any(|pair| pair[0].from >= pair[1].from) {
+                    return Err(invalid("tiers must be sorted by from"));
+                }
+            }
+        }
 
-        let spread = collect_coins(funds, ATOM_DENOM)?;
+        Ok(())
+    }
 
-        validiate_bid(&state, &spread)?;
+    pub fn validiate_schedule(
+        block: &BlockInfo,
+        start: Option<&Scheduled>,
+        end: Option<&Expiration>,
+    ) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidScheduleErr {
+            reason: reason.into(),
+        };
 
-        let current_bid = update_state(&mut state, sender, bid, &spread)?;
+        match end {
+            Some(Expiration::Never {}) => return Err(invalid("end must be a height or time")),
+            Some(end) if end.is_expired(block) => return Err(invalid("end already passed")),
+            _ => {}
+        }
 
-        // save the state and bids
-        STATE.save(deps.storage, &state)?;
-        BIDDINGS.save(deps.storage, sender.clone(), &current_bid)?;
+        let start_before_end = match (start, end) {
+            (Some(Scheduled::AtHeight(start)), Some(Expiration::AtHeight(end))) => start < end,
+            (Some(Scheduled::AtTime(start)), Some(Expiration::AtTime(end))) => start < end,
+            _ => true,
+        };
 
-        let resp = Response::new()
-            .add_attribute("action", "bid")
-            .add_attribute("sender", sender)
-            .add_attribute("spread", spread.amount.to_string());
+        if !start_before_end {
+            return Err(invalid("start must be before end"));
+        }
 
-        Ok(resp)
+        Ok(())
     }
 
-    pub fn close(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-        let sender = info.sender;
-        let mut state = STATE.load(deps.storage)?;
+    pub fn validiate_extension(
+        end: Option<&Expiration>,
+        extension: Option<&Extension>,
+    ) -> Result<(), ContractError> {
+        let Some(extension) = extension else {
+            return Ok(());
+        };
 
-        validiate_owner(&sender, &state.owner)?;
+        let invalid = |reason: &str| ContractError::InvalidExtensionErr {
+            reason: reason.into(),
+        };
 
-        let highest = state.highest.as_ref();
+        let Some(Expiration::AtTime(end)) = end else {
+            return Err(invalid("end must be a time"));
+        };
 
-        state.status = BidStatus::Closed {};
-        state.winner = highest.map(|bid| bid.bidder.clone());
+        if extension.duration == 0 {
+            return Err(invalid("duration must be greater than zero"));
+        }
 
-        let highest_coin: Vec<_> = highest.map(|bid| bid.bid.clone()).into_iter().collect();
+        if extension.hard_cap.is_some_and(|hard_cap| hard_cap < *end) {
+            return Err(invalid("hard cap must not be before end"));
+        }
 
-        STATE.save(deps.storage, &state)?;
+        Ok(())
+    }
 
-        let contract_balances = deps.querier.query_all_balances(env.contract.address)?;
+    pub fn validiate_opening(state: &State, block: &BlockInfo) -> Result<(), ContractError> {
+        if state.paused {
+            return Err(ContractError::BidPausedErr {});
+        }
 
-        validiate_balances(&contract_balances, &highest_coin)?;
+        match state.status_at(block) {
+            BidStatus::Closed {} => Err(ContractError::BidAlreadyClosed {}),
+            BidStatus::Cancelled {} => Err(ContractError::BidCancelledErr {}),
+            BidStatus::NotStarted {} => Err(ContractError::BidNotStarted {}),
+            BidStatus::Opening {} if state.is_ended(block) => {
+                Err(ContractError::BidAlreadyEnded {})
+            }
+            BidStatus::Opening {} => Ok(()),
+        }
+    }
+
+    /// Check the revealed reserve against the commitment and record it, so the outcome
+    /// can be audited after close
+    pub fn reveal_reserve(
+        state: &mut State,
+        reveal: Option<Reveal>,
+        block: &BlockInfo,
+    ) -> Result<(), ContractError> {
+        match (&state.reserve_commitment, reveal) {
+            (None, None) => Ok(()),
+            // a reserve left unrevealed past the reveal period is dropped, the sale goes on
+            (Some(_), None)
+                if state
+                    .reserve_reveal_end()
+                    .is_some_and(|reveal_end| reveal_end.is_expired(block)) =>
+            {
+                state.reserve_commitment = None;
+                Ok(())
+            }
+            // the owner can still reveal
+            (Some(_), None) if state.is_ended(block) => Err(ContractError::RevealOpeningErr {}),
+            (Some(commitment), Some(reveal))
+                if Reserve::commitment(reveal.reserve, &reveal.salt) == *commitment =>
+            {
+                state.reserve = Some(Reserve {
+                    price: reveal.reserve,
+                    hidden: false,
+                });
+                Ok(())
+            }
+            _ => Err(ContractError::InvalidRevealErr {}),
+        }
+    }
 
-        // transfer funds to owner
-        let bank_msg = BankMsg::Send {
-            to_address: sender.to_string(),
-            amount: highest_coin,
+    /// Reveal between the end and the reveal end of a sealed bid, even while paused
+    pub fn can_reveal(state: &State, block: &BlockInfo) -> Result<(), ContractError> {
+        let Some(reveal_end) = state.reveal_end() else {
+            return Err(ContractError::WrongAuctionKindErr {});
         };
 
-        let resp = Response::new()
-            .add_message(bank_msg)
-            .add_attribute("action", "close")
-            .add_attribute("sender", sender);
+        if state.status.is_cancelled() {
+            return Err(ContractError::BidCancelledErr {});
+        }
 
-        Ok(resp)
-    }
+        if state.status.is_closed() {
+            return Err(ContractError::BidAlreadyClosed {});
+        }
 
-    pub fn retract(
-        deps: DepsMut,
-        env: Env,
-        info: MessageInfo,
-        receiver: Option<String>,
-    ) -> Result<Response, ContractError> {
-        let sender = info.sender;
+        if !state.is_ended(block) {
+            return Err(ContractError::BidIsOpening {});
+        }
 
-        let state = STATE.load(deps.storage)?;
+        if reveal_end.is_expired(block) {
+            return Err(ContractError::RevealEndedErr {});
+        }
 
-        can_retract(&state, &sender)?;
+        Ok(())
+    }
 
-        let owner = state.owner;
+    // Before the end only the owner can close, after the end anyone can, paused or not
+    pub fn can_close(state: &State, sender: &Addr, block: &BlockInfo) -> Result<(), ContractError> {
+        if state.status.is_cancelled() {
+            return Err(ContractError::BidCancelledErr {});
+        }
 
-        can_bid(&sender, &owner)?;
+        if state.status.is_closed() {
+            return Err(ContractError::BidAlreadyClosed {});
+        }
 
-        let receiver = &receiver
-            .as_ref()
-            .map(Addr::unchecked)
-            .unwrap_or(sender.clone());
-        let bid = BIDDINGS.may_load(deps.storage, receiver.clone())?;
+        // a sealed bid waits for every reveal
+        if state
+            .reveal_end()
+            .is_some_and(|reveal_end| !reveal_end.is_expired(block))
+        {
+            return match state.is_ended(block) {
+                true => Err(ContractError::RevealOpeningErr {}),
+                false => Err(ContractError::BidIsOpening {}),
+            };
+        }
 
-        let resp = if let Some(bid) = bid {
-            if bid.amount.is_zero() {
-                return Err(ContractError::Unauthorized {});
-            }
+        match &state.end {
+            Some(_) if state.is_ended(block) => Ok(()),
+            Some(_) => Err(ContractError::BidIsOpening {}),
+            None => validiate_owner(sender, state.owner.as_ref()),
+        }
+    }
 
-            let bids = vec![coin(
-                bid.amount
-                    .checked_sub(state.commission.amount)
-                    .unwrap()
-                    .u128(),
-                ATOM_DENOM,
-            )];
+    pub fn validiate_weights(denom: &str, weights: &[DenomWeight]) -> Result<(), ContractError> {
+        validiate_denom_name(denom)?;
 
-            let contract_balances = deps.querier.query_all_balances(env.contract.address)?;
-            validiate_balances(&contract_balances, &bids)?;
+        for (i, w) in weights.iter().enumerate() {
+            validiate_denom_name(&w.denom)?;
 
-            let bank_msg = BankMsg::Send {
-                to_address: receiver.to_string(),
-                amount: bids,
-            };
+            let duplicated =
+                w.denom == denom || weights[..i].iter().any(|other| other.denom == w.denom);
 
-            Response::new().add_message(bank_msg)
-        } else {
-            Response::new()
+            if w.weight.is_zero() || duplicated {
+                return Err(ContractError::InvalidDenomErr {
+                    denom: w.denom.clone(),
+                });
+            }
         }
-        .add_attribute("action", "retract")
-        .add_attribute("sender", sender)
-        .add_attribute("receiver", receiver);
 
-        Ok(resp)
+        Ok(())
     }
 
-    pub fn validiate_bid(state: &State, spread: &Coin) -> Result<(), ContractError> {
-        if spread.amount < state.tick.amount || spread.amount < state.commission.amount {
-            return Err(ContractError::InvalidBidErr {
-                total_bid: spread.clone(),
+    // Only native coins are accepted as funds, cw20 tokens come through `receive`
+    pub fn validiate_denom(denom: &[Coin], state: &State) -> Result<(), ContractError> {
+        if let Some(c) = denom
+            .iter()
+            .find(|c| state.weight(&c.denom).is_none() || state.is_cw20(&c.denom))
+        {
+            return Err(ContractError::CoinSupportedOnlyErr {
+                denom: c.denom.clone(),
             });
         }
 
         Ok(())
     }
 
-    pub fn validiate_denom(denom: &[Coin], atom_denom: &str) -> Result<(), ContractError> {
-        if denom.iter().any(|c| c.denom != atom_denom) {
+    pub fn validiate_cw20(sender: &Addr, state: &State) -> Result<(), ContractError> {
+        if !state.is_cw20(sender.as_str()) {
             return Err(ContractError::CoinSupportedOnlyErr {
-                denom: atom_denom.into(),
+                denom: sender.to_string(),
             });
         }
 
         Ok(())
     }
 
-    pub fn can_bid(sender: &Addr, owner: &Addr) -> Result<(), ContractError> {
+    pub fn can_bid(sender: &Addr, owner: Option<&Addr>) -> Result<(), ContractError> {
         if is_owner(sender, owner) {
             return Err(ContractError::Unauthorized {});
         }
@@ -199,7 +1873,7 @@ pub mod exec {
         Ok(())
     }
 
-    pub fn validiate_owner(sender: &Addr, owner: &Addr) -> Result<(), ContractError> {
+    pub fn validiate_owner(sender: &Addr, owner: Option<&Addr>) -> Result<(), ContractError> {
         if !is_owner(sender, owner) {
             return Err(ContractError::Unauthorized {});
         }
@@ -207,30 +1881,137 @@ pub mod exec {
         Ok(())
     }
 
-    pub fn is_owner(sender: &Addr, owner: &Addr) -> bool {
-        owner == sender
+    pub fn is_owner(sender: &Addr, owner: Option<&Addr>) -> bool {
+        owner == Some(sender)
     }
 
     pub fn validiate_balances(
         contract_balances: &[Coin],
         highest_coin: &[Coin],
     ) -> Result<(), ContractError> {
-        let contract_total = collect_coins(contract_balances, ATOM_DENOM)?;
-        let highest_total = collect_coins(highest_coin, ATOM_DENOM)?;
+        for c in highest_coin {
+            let contract_total = contract_balances
+                .iter()
+                .find(|b| b.denom == c.denom)
+                .cloned()
+                .unwrap_or_else(|| Coin::new(0, &c.denom));
+
+            if contract_total.amount < c.amount {
+                return Err(ContractError::ContractBalanceInvalidErr {
+                    amount: contract_total,
+                });
+            }
+        }
 
-        if contract_total.amount >= highest_total.amount {
-            Ok(())
-        } else {
-            Err(ContractError::ContractBalanceInvalidErr {
-                amount: contract_total,
-            })
+        Ok(())
+    }
+
+    /// The native balances of the contract plus its balance of the bidding cw20 token
+    pub fn query_balances(deps: Deps, state: &State, contract: &Addr) -> StdResult<Vec<Coin>> {
+        let mut balances = deps.querier.query_all_balances(contract)?;
+
+        if let Some(cw20) = &state.cw20 {
+            let resp: BalanceResponse = deps.querier.query_wasm_smart(
+                cw20,
+                &Cw20QueryMsg::Balance {
+                    address: contract.to_string(),
+                },
+            )?;
+            balances.push(Coin::new(resp.balance.u128(), cw20));
+        }
+
+        Ok(balances)
+    }
+
+    /// Send native coins with the bank module and the cw20 token with a transfer
+    pub fn send_coins(state: &State, to: &Addr, coins: Vec<Coin>) -> StdResult<Vec<CosmosMsg>> {
+        let (cw20, native): (Vec<_>, Vec<_>) = coins
+            .into_iter()
+            .filter(|c| !c.amount.is_zero())
+            .partition(|c| state.is_cw20(&c.denom));
+
+        let mut msgs = vec![];
+
+        if !native.is_empty() {
+            msgs.push(
+                BankMsg::Send {
+                    to_address: to.to_string(),
+                    amount: native,
+                }
+                .into(),
+            );
+        }
+
+        for c in cw20 {
+            msgs.push(
+                WasmMsg::Execute {
+                    contract_addr: c.denom,
+                    msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: to.to_string(),
+                        amount: c.amount,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            );
+        }
+
+        Ok(msgs)
+    }
+
+    pub fn transfer_nft(item: &AuctionItem, recipient: &Addr) -> StdResult<CosmosMsg> {
+        let msg = WasmMsg::Execute {
+            contract_addr: item.contract.to_string(),
+            msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: item.token_id.clone(),
+            })?,
+            funds: vec![],
+        };
+
+        Ok(msg.into())
+    }
+
+    /// Split the commission off the funds, taking it from each denom in order,
+    /// returns the net bid and the commission
+    pub fn split_commission(
+        state: &State,
+        funds: &[Coin],
+        fee: Uint128,
+    ) -> Result<(Vec<Coin>, Vec<Coin>), ContractError> {
+        let mut remaining = fee;
+        let mut net = vec![];
+        let mut commission = vec![];
+
+        for fund in funds {
+            if remaining.is_zero() {
+                net.push(fund.clone());
+                continue;
+            }
+
+            let weight = state.weight(&fund.denom).unwrap_or_else(Decimal::one);
+            let value = fund.amount.mul_floor(weight);
+
+            if value <= remaining {
+                remaining -= value;
+                commission.push(fund.clone());
+                continue;
+            }
+
+            let charged = remaining.div_ceil(weight);
+            remaining = Uint128::zero();
+
+            net.push(Coin::new((fund.amount - charged).u128(), &fund.denom));
+            commission.push(Coin::new(charged.u128(), &fund.denom));
         }
+
+        Ok((net, commission))
     }
 
-    // Owner and winner can't retract
+    // Owner and winner can't retract, unless the winner keeps part of its deposit
     pub fn can_retract(state: &State, sender: &Addr) -> Result<(), ContractError> {
-        if state.owner == sender
-            || state.winner == Some(sender.clone())
+        if state.is_owner(sender)
+            || (state.winner == Some(sender.clone()) && !state.winner_retracts())
             || !state.status.is_closed()
         {
             return Err(ContractError::Unauthorized {});
@@ -245,82 +2026,505 @@ pub mod exec {
         bid: Option<Coin>,
         spread: &Coin,
     ) -> Result<Coin, ContractError> {
-        let current_bid = add_coin(&bid.unwrap_or_else(|| Coin::new(0, ATOM_DENOM)), spread)?;
+        let current_bid = add_coin(&bid.unwrap_or_else(|| Coin::new(0, &state.denom)), spread)?;
 
-        let current_amount = current_bid.amount;
-        let highest_amount = state
-            .highest
-            .as_ref()
-            .map(|b| b.bid.amount)
-            .unwrap_or_default();
+        let minimum = state.minimum_total();
+
+        // a penny bid raises the price by the tick only
+        if matches!(state.kind, AuctionKind::Penny { .. }) && current_bid.amount > minimum {
+            return Err(ContractError::PennyBidErr {
+                price: Coin::new(minimum.u128(), &state.denom),
+            });
+        }
 
-        if current_amount > highest_amount {
+        if current_bid.amount >= minimum {
             let highest = Bid {
                 bid: current_bid.clone(),
                 bidder: sender.clone(),
             };
 
+            // the outbid highest becomes the runner-up, a top up keeps it
+            if let Some(outbid) = state.highest.take().filter(|b| b.bidder != *sender) {
+                state.runner_up = Some(outbid);
+            }
             state.highest = Some(highest);
 
             Ok(current_bid)
         } else {
             Err(ContractError::BidTooLowErr {
-                less_than: current_bid,
+                minimum: Coin::new(minimum.u128(), &state.denom),
+                total: current_bid,
             })
         }
     }
 }
 
+pub mod migrate {
+    use cosmwasm_std::{Coin, CosmosMsg, DepsMut, Env, Order, Response, StdResult};
+    use cw2::{get_contract_version, set_contract_version};
+    use semver::Version;
+
+    use crate::{
+        helper::add_coins,
+        msg::MigrateMsg,
+        state::{
+            v0_1, Bid, CommissionModel, Config, Deposit, State, AUCTION_SEQ, BIDDINGS, CONFIG,
+            STATE,
+        },
+        ContractError,
+    };
+
+    use super::{exec::send_coins, CONTRACT_NAME, CONTRACT_VERSION};
+
+    pub fn migrate(
+        mut deps: DepsMut,
+        _env: Env,
+        _msg: MigrateMsg,
+    ) -> Result<Response, ContractError> {
+        let stored = get_contract_version(deps.storage)?;
+
+        if stored.contract != CONTRACT_NAME {
+            return Err(ContractError::InvalidMigrationErr {
+                reason: format!("cannot migrate from {}", stored.contract),
+            });
+        }
+
+        let from = parse_version(&stored.version)?;
+        let to = parse_version(CONTRACT_VERSION)?;
+
+        if from > to {
+            return Err(ContractError::InvalidMigrationErr {
+                reason: format!("cannot downgrade from {from} to {to}"),
+            });
+        }
+
+        let mut resp = Response::new();
+
+        if from < Version::new(0, 2, 0) {
+            resp = resp.add_messages(from_v0_1(deps.branch())?);
+        }
+
+        set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        Ok(resp
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", from.to_string())
+            .add_attribute("to_version", to.to_string()))
+    }
+
+    /// Move the single v0.1 auction into auction 1 and split the commission off each
+    /// deposit, the commission is sent to the owner as bids do now. A closed v0.1 auction
+    /// is refused, its retract kept the deposits so they cannot be told apart
+    pub fn from_v0_1(deps: DepsMut) -> Result<Vec<CosmosMsg>, ContractError> {
+        let legacy = v0_1::STATE.load(deps.storage)?;
+
+        if matches!(legacy.status, v0_1::BidStatus::Closed {}) {
+            return Err(ContractError::InvalidMigrationErr {
+                reason: "cannot migrate a closed auction".into(),
+            });
+        }
+
+        let commission = legacy.commission.amount;
+
+        let net = |c: &Coin| Coin::new(c.amount.saturating_sub(commission).u128(), &c.denom);
+
+        let mut state = State::new(
+            legacy.owner.clone(),
+            legacy.name,
+            legacy.tick.denom.clone(),
+            legacy.tick,
+            CommissionModel::Flat { amount: commission },
+        );
+        state.highest = legacy.highest.map(|highest| Bid {
+            bid: net(&highest.bid),
+            bidder: highest.bidder,
+        });
+
+        let biddings = v0_1::BIDDINGS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut commissions = vec![];
+        for (bidder, total) in biddings {
+            v0_1::BIDDINGS.remove(deps.storage, bidder.clone());
+
+            let deposit = Deposit {
+                bid: vec![net(&total)],
+                commission: vec![Coin::new(total.amount.min(commission).u128(), &total.denom)],
+            };
+            add_coins(&mut commissions, &deposit.commission);
+            BIDDINGS.save(deps.storage, (1, bidder), &deposit)?;
+        }
+
+        v0_1::STATE.remove(deps.storage);
+        state.collected = commissions.clone();
+
+        let config = Config {
+            admin: legacy.owner.clone(),
+            creators: Some(vec![]),
+        };
+        CONFIG.save(deps.storage, &config)?;
+        AUCTION_SEQ.save(deps.storage, &1)?;
+        STATE.save(deps.storage, 1, &state)?;
+
+        Ok(send_coins(&state, &legacy.owner, commissions)?)
+    }
+
+    fn parse_version(version: &str) -> Result<Version, ContractError> {
+        version
+            .parse()
+            .map_err(|e: semver::Error| ContractError::InvalidMigrationErr {
+                reason: e.to_string(),
+            })
+    }
+}
+
 pub mod query {
-    use cosmwasm_std::{coin, to_binary, Addr, Binary, Deps, Env, StdResult};
+    use cosmwasm_std::{
+        to_json_binary, Addr, Binary, Coin, Deps, Env, Order, StdError, StdResult, Uint128,
+    };
+    use cw_storage_plus::Bound;
+    use cw_utils::{Duration, Expiration};
 
     use crate::{
-        msg::{HighestOfBidResp, QueryMsg, TotalBidResp, WinnerResp},
-        state::{BIDDINGS, STATE},
-        ATOM_DENOM,
+        msg::{
+            AuctionItemResp, AuctionResp, AuctionsResp, BuyNowResp, ClearingPriceResp,
+            CommissionResp, CommitmentResp, ConfigResp, CurrentPriceResp, DeadlineResp,
+            HighestOfBidResp, MinimumNextBidResp, OwnershipResp, PreviewCommissionResp, QueryMsg,
+            ReserveResp, StatusResp, TotalBidResp, UnitAllocationResp, WinnerResp,
+        },
+        state::{
+            unit_allocations, unit_bids, AuctionKind, AUCTION_SEQ, BIDDINGS, COMMITMENTS, CONFIG,
+            STATE,
+        },
     };
     use QueryMsg::*;
 
-    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+
+    pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
-            TotalBid { addr } => query_total_bid(deps, &addr).and_then(|tb| to_binary(&tb)),
+            Config {} => query_config(deps).and_then(|c| to_json_binary(&c)),
+
+            Auctions { start_after, limit } => {
+                query_auctions(deps, env, start_after, limit).and_then(|a| to_json_binary(&a))
+            }
+
+            TotalBid { auction_id, addr } => {
+                query_total_bid(deps, auction_id, &addr).and_then(|tb| to_json_binary(&tb))
+            }
+
+            HighestOfBid { auction_id } => {
+                query_highest_of_bid(deps, auction_id).and_then(|hb| to_json_binary(&hb))
+            }
+
+            Winner { auction_id } => {
+                query_winner(deps, auction_id).and_then(|w| to_json_binary(&w))
+            }
+
+            Status { auction_id } => {
+                query_status(deps, env, auction_id).and_then(|s| to_json_binary(&s))
+            }
+
+            Deadline { auction_id } => {
+                query_deadline(deps, env, auction_id).and_then(|d| to_json_binary(&d))
+            }
+
+            AuctionItem { auction_id } => {
+                query_auction_item(deps, auction_id).and_then(|i| to_json_binary(&i))
+            }
+
+            Reserve { auction_id } => {
+                query_reserve(deps, auction_id).and_then(|r| to_json_binary(&r))
+            }
+            MinimumNextBid { auction_id, addr } => {
+                query_minimum_next_bid(deps, auction_id, addr).and_then(|r| to_json_binary(&r))
+            }
+            UnitAllocation { auction_id, addr } => {
+                query_unit_allocation(deps, auction_id, addr).and_then(|r| to_json_binary(&r))
+            }
+            CurrentPrice { auction_id } => {
+                query_current_price(deps, env, auction_id).and_then(|r| to_json_binary(&r))
+            }
+            ClearingPrice { auction_id } => {
+                query_clearing_price(deps, auction_id).and_then(|r| to_json_binary(&r))
+            }
+            Commitment { auction_id, addr } => {
+                query_commitment(deps, auction_id, addr).and_then(|r| to_json_binary(&r))
+            }
+            BuyNow { auction_id } => {
+                query_buy_now(deps, auction_id).and_then(|r| to_json_binary(&r))
+            }
+
+            Ownership { auction_id } => {
+                query_ownership(deps, auction_id).and_then(|o| to_json_binary(&o))
+            }
 
-            HighestOfBid {} => query_highest_of_bid(deps).and_then(|hb| to_binary(&hb)),
+            Commission { auction_id } => {
+                query_commission(deps, auction_id).and_then(|c| to_json_binary(&c))
+            }
 
-            Winner {} => query_winner(deps).and_then(|w| to_binary(&w)),
+            PreviewCommission { auction_id, amount } => {
+                query_preview_commission(deps, auction_id, amount).and_then(|c| to_json_binary(&c))
+            }
         }
     }
 
-    pub fn query_total_bid(deps: Deps, sender: &str) -> StdResult<TotalBidResp> {
-        let bid = BIDDINGS.may_load(deps.storage, Addr::unchecked(sender))?;
+    pub fn query_config(deps: Deps) -> StdResult<ConfigResp> {
+        let config = CONFIG.load(deps.storage)?;
 
-        if let Some(bid) = bid {
-            let state = STATE.load(deps.storage)?;
+        Ok(ConfigResp {
+            admin: config.admin,
+            creators: config.creators,
+            auction_count: AUCTION_SEQ.load(deps.storage)?,
+        })
+    }
 
-            Ok(TotalBidResp {
-                total: coin(
-                    bid.amount.checked_sub(state.commission.amount)?.u128(),
-                    ATOM_DENOM,
-                ),
-            })
-        } else {
-            Ok(TotalBidResp {
-                total: coin(0, ATOM_DENOM),
+    pub fn query_auctions(
+        deps: Deps,
+        env: Env,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let min = start_after.map(Bound::exclusive);
+
+        let auctions = STATE
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(auction_id, state)| AuctionResp {
+                    auction_id,
+                    status: state.status_at(&env.block),
+                    beneficiary: state.beneficiary().cloned(),
+                    name: state.name,
+                    owner: state.owner,
+                })
             })
-        }
+            .collect::<StdResult<_>>()?;
+
+        Ok(AuctionsResp { auctions })
     }
 
-    pub fn query_highest_of_bid(deps: Deps) -> StdResult<HighestOfBidResp> {
-        let state = STATE.load(deps.storage)?;
+    pub fn query_total_bid(deps: Deps, auction_id: u64, sender: &str) -> StdResult<TotalBidResp> {
+        let deposit = BIDDINGS
+            .may_load(deps.storage, (auction_id, Addr::unchecked(sender)))?
+            .unwrap_or_default();
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        let normalize = |coins: &[Coin]| {
+            state
+                .normalize(coins)
+                .map_err(|e| StdError::generic_err(e.to_string()))
+        };
+
+        Ok(TotalBidResp {
+            total: normalize(&deposit.bid)?,
+            commission: normalize(&deposit.commission)?,
+        })
+    }
+
+    pub fn query_highest_of_bid(deps: Deps, auction_id: u64) -> StdResult<HighestOfBidResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(HighestOfBidResp {
+            bid: state.highest,
+            runner_up: state.runner_up,
+        })
+    }
+
+    /// The allocation if the multi-unit auction closed now, final once closed
+    pub fn query_unit_allocation(
+        deps: Deps,
+        auction_id: u64,
+        addr: String,
+    ) -> StdResult<UnitAllocationResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+        let addr = deps.api.addr_validate(&addr)?;
+
+        let AuctionKind::MultiUnit { quantity } = state.kind else {
+            return Err(StdError::generic_err("not a multi-unit auction"));
+        };
+
+        let (allocations, price) = unit_allocations(deps.storage, auction_id, quantity)?;
+        let units = allocations
+            .into_iter()
+            .find(|(bidder, _)| *bidder == addr)
+            .map(|(_, units)| units)
+            .unwrap_or_default();
+
+        Ok(UnitAllocationResp {
+            bid: unit_bids().may_load(deps.storage, (auction_id, addr))?,
+            units,
+            clearing_price: price.map(|price| Coin::new(price.u128(), &state.denom)),
+        })
+    }
+
+    pub fn query_current_price(
+        deps: Deps,
+        env: Env,
+        auction_id: u64,
+    ) -> StdResult<CurrentPriceResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        let price = state
+            .current_price(&env.block)
+            .ok_or_else(|| StdError::generic_err("not a dutch auction"))?;
+
+        Ok(CurrentPriceResp {
+            price: Coin::new(price.u128(), &state.denom),
+        })
+    }
+
+    pub fn query_clearing_price(deps: Deps, auction_id: u64) -> StdResult<ClearingPriceResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
 
-        Ok(HighestOfBidResp { bid: state.highest })
+        Ok(ClearingPriceResp {
+            price: state.clearing_price,
+        })
     }
 
-    pub fn query_winner(deps: Deps) -> StdResult<WinnerResp> {
-        let state = STATE.load(deps.storage)?;
+    pub fn query_winner(deps: Deps, auction_id: u64) -> StdResult<WinnerResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
 
         Ok(WinnerResp {
             winner: state.winner,
         })
     }
+
+    pub fn query_status(deps: Deps, env: Env, auction_id: u64) -> StdResult<StatusResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(StatusResp {
+            status: state.status_at(&env.block),
+            start: state.start,
+            end: state.end,
+            paused: state.paused,
+            emergency: state.emergency,
+        })
+    }
+
+    pub fn query_deadline(deps: Deps, env: Env, auction_id: u64) -> StdResult<DeadlineResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        let remaining = state.end.as_ref().and_then(|end| match end {
+            Expiration::AtHeight(height) => {
+                Some(Duration::Height(height.saturating_sub(env.block.height)))
+            }
+            Expiration::AtTime(time) => Some(Duration::Time(
+                time.seconds().saturating_sub(env.block.time.seconds()),
+            )),
+            Expiration::Never {} => None,
+        });
+
+        Ok(DeadlineResp {
+            end: state.end,
+            hard_cap: state.extension.and_then(|extension| extension.hard_cap),
+            remaining,
+        })
+    }
+
+    pub fn query_auction_item(deps: Deps, auction_id: u64) -> StdResult<AuctionItemResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(AuctionItemResp {
+            nft: state.nft,
+            item: state.item,
+        })
+    }
+
+    pub fn query_reserve(deps: Deps, auction_id: u64) -> StdResult<ReserveResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(ReserveResp {
+            has_reserve: state.reserve.is_some() || state.reserve_commitment.is_some(),
+            commitment: state.reserve_commitment.clone(),
+            met: state.is_reserve_met(),
+            price: state
+                .reserve
+                .filter(|reserve| !reserve.hidden)
+                .map(|reserve| reserve.price),
+        })
+    }
+
+    pub fn query_minimum_next_bid(
+        deps: Deps,
+        auction_id: u64,
+        addr: String,
+    ) -> StdResult<MinimumNextBidResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+        let addr = deps.api.addr_validate(&addr)?;
+
+        let deposit = BIDDINGS
+            .may_load(deps.storage, (auction_id, addr))?
+            .unwrap_or_default();
+        let total = state
+            .normalize(&deposit.bid)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        // the net amount still missing, grossed up by the commission and at least a tick
+        let missing = state.minimum_total().saturating_sub(total.amount);
+        let amount = state
+            .commission
+            .gross(missing)
+            .ok_or_else(|| StdError::generic_err("the commission takes the whole bid"))?
+            .max(state.tick.amount);
+
+        Ok(MinimumNextBidResp {
+            amount: Coin::new(amount.u128(), &state.denom),
+        })
+    }
+
+    pub fn query_commitment(
+        deps: Deps,
+        auction_id: u64,
+        addr: String,
+    ) -> StdResult<CommitmentResp> {
+        let addr = deps.api.addr_validate(&addr)?;
+        let commitment = COMMITMENTS.may_load(deps.storage, (auction_id, addr))?;
+
+        Ok(CommitmentResp { commitment })
+    }
+
+    pub fn query_buy_now(deps: Deps, auction_id: u64) -> StdResult<BuyNowResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(BuyNowResp {
+            buy_now: state.buy_now,
+        })
+    }
+
+    pub fn query_ownership(deps: Deps, auction_id: u64) -> StdResult<OwnershipResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(OwnershipResp {
+            owner: state.owner,
+            pending_owner: state.pending_owner,
+            pending_expiry: state.pending_expiry,
+        })
+    }
+
+    pub fn query_commission(deps: Deps, auction_id: u64) -> StdResult<CommissionResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(CommissionResp {
+            fee_collector: state.fee_collector().cloned(),
+            model: state.commission,
+        })
+    }
+
+    pub fn query_preview_commission(
+        deps: Deps,
+        auction_id: u64,
+        amount: Uint128,
+    ) -> StdResult<PreviewCommissionResp> {
+        let state = STATE.load(deps.storage, auction_id)?;
+        let commission = state.commission.commission(amount);
+
+        Ok(PreviewCommissionResp {
+            commission: Coin::new(commission.u128(), &state.denom),
+            net: Coin::new(amount.saturating_sub(commission).u128(), &state.denom),
+        })
+    }
 }
@@ -1,4 +1,5 @@
 use cosmwasm_std::{Coin, StdError};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -15,8 +19,95 @@ pub enum ContractError {
     #[error("Bid is opening")]
     BidIsOpening {},
 
-    #[error("The bid is {less_than} lower than the highest price")]
-    BidTooLowErr { less_than: Coin },
+    #[error("Bid not started")]
+    BidNotStarted {},
+
+    #[error("Bid already ended")]
+    BidAlreadyEnded {},
+
+    #[error("Bid cancelled")]
+    BidCancelledErr {},
+
+    #[error("The collected commission must be returned: {required:?}")]
+    CommissionRefundErr { required: Vec<Coin> },
+
+    #[error("Bid is paused")]
+    BidPausedErr {},
+
+    #[error("Invalid schedule: {reason}")]
+    InvalidScheduleErr { reason: String },
+
+    #[error("Invalid extension: {reason}")]
+    InvalidExtensionErr { reason: String },
+
+    #[error("No ownership proposal")]
+    NoOwnershipProposalErr {},
+
+    #[error("Ownership proposal expired")]
+    OwnershipProposalExpiredErr {},
+
+    #[error("Ownership can't be renounced without an end")]
+    RenounceWithoutEndErr {},
+
+    #[error("Invalid migration: {reason}")]
+    InvalidMigrationErr { reason: String },
+
+    #[error("Invalid commission: {reason}")]
+    InvalidCommissionErr { reason: String },
+
+    #[error("Invalid reserve: {reason}")]
+    InvalidReserveErr { reason: String },
+
+    #[error("The increment must not exceed 10000 bps")]
+    InvalidIncrementErr {},
+
+    #[error("Invalid auction kind: {reason}")]
+    InvalidAuctionKindErr { reason: String },
+
+    #[error("Not supported by this auction kind")]
+    WrongAuctionKindErr {},
+
+    #[error("The commitment must be a sha256 hash")]
+    InvalidCommitmentErr {},
+
+    #[error("The bid is already committed")]
+    BidAlreadyCommittedErr {},
+
+    #[error("The revealed bid does not match the commitment")]
+    InvalidBidRevealErr {},
+
+    #[error("The reveal phase is still open")]
+    RevealOpeningErr {},
+
+    #[error("The reveal phase already ended")]
+    RevealEndedErr {},
+
+    #[error("The budget {budget} must be escrowed")]
+    BudgetNotEscrowedErr { budget: Coin },
+
+    #[error("Between 1 and {max} units can be bid")]
+    InvalidUnitsErr { max: u64 },
+
+    #[error("A bond of {bond} must be posted")]
+    InvalidBondErr { bond: Coin },
+
+    #[error("The ask must not be zero")]
+    InvalidAskErr {},
+
+    #[error("The ask {price} is above the maximum {maximum}")]
+    AskTooHighErr { price: Coin, maximum: Coin },
+
+    #[error("Invalid buy now: {reason}")]
+    InvalidBuyNowErr { reason: String },
+
+    #[error("The revealed reserve does not match the commitment")]
+    InvalidRevealErr {},
+
+    #[error("The bid total {total} is lower than the minimum {minimum}")]
+    BidTooLowErr { total: Coin, minimum: Coin },
+
+    #[error("A penny bid must raise the total to exactly {price}")]
+    PennyBidErr { price: Coin },
 
     #[error("Coin not same: {first} = {second}")]
     CoinOperationErr { first: String, second: String },
@@ -24,6 +115,12 @@ pub enum ContractError {
     #[error("Coin not supported: {denom}")]
     CoinSupportedOnlyErr { denom: String },
 
+    #[error("Nft already deposited")]
+    NftAlreadyDepositedErr {},
+
+    #[error("Invalid denom: {denom}")]
+    InvalidDenomErr { denom: String },
+
     #[error("Contract balance invalid: {amount}")]
     ContractBalanceInvalidErr { amount: Coin },
 
@@ -24,6 +24,16 @@ pub fn add_coin(first: &Coin, second: &Coin) -> Result<Coin, ContractError> {
     }
 }
 
+/// Merge the coins into the list, keeping one coin per denom
+pub fn add_coins(coins: &mut Vec<Coin>, other: &[Coin]) {
+    for c in other.iter().filter(|c| !c.amount.is_zero()) {
+        match coins.iter_mut().find(|acc| acc.denom == c.denom) {
+            Some(acc) => acc.amount += c.amount,
+            None => coins.push(c.clone()),
+        }
+    }
+}
+
 pub fn validiate_denom(coins: &[Coin], denom: &str) -> Result<(), ContractError> {
     if coins.iter().any(|c| c.denom != denom) {
         return Err(ContractError::CoinSupportedOnlyErr {
@@ -34,6 +44,23 @@ pub fn validiate_denom(coins: &[Coin], denom: &str) -> Result<(), ContractError>
     Ok(())
 }
 
+/// Validate the denom follows the cosmos sdk rule `[a-zA-Z][a-zA-Z0-9/:._-]{2,127}`
+pub fn validiate_denom_name(denom: &str) -> Result<(), ContractError> {
+    let valid = (3..=128).contains(&denom.len())
+        && denom.starts_with(|c: char| c.is_ascii_alphabetic())
+        && denom
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+
+    if !valid {
+        return Err(ContractError::InvalidDenomErr {
+            denom: denom.into(),
+        });
+    }
+
+    Ok(())
+}
+
 pub fn ten_atom() -> Coin {
     Coin::new(10, ATOM_DENOM)
 }
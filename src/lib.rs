@@ -4,7 +4,7 @@ pub mod helper;
 pub mod msg;
 pub mod state;
 
-#[cfg(any(test, features = "tests"))]
+#[cfg(any(test, feature = "tests"))]
 pub mod multitest;
 
 pub use crate::error::ContractError;
@@ -16,7 +16,7 @@ pub const CONTRACT_LABEL: &str = "bidding_label";
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -43,5 +43,10 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     contract::query::query(deps, env, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    contract::migrate::migrate(deps, env, msg)
+}
+
 #[cfg(test)]
 mod tests {}
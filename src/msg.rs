@@ -1,54 +1,382 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Binary, Coin, HexBinary, Timestamp, Uint128};
+use cw20::{Cw20ReceiveMsg, UncheckedDenom};
+use cw721::Cw721ReceiveMsg;
+use cw_utils::{Duration, Expiration, Scheduled};
 
-use crate::state::Bid;
+use crate::state::{
+    AuctionItem, AuctionKind, Bid, BidStatus, BuyNow, CommissionModel, Commitment, DenomWeight,
+    Extension, Reserve, UnitBid,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
+    /// Addresses allowed to open auctions, anyone if missing
+    pub creators: Option<Vec<String>>,
+    /// The first auction, opened by the sender
+    pub auction: Option<Box<AuctionMsg>>,
+}
+
+/// A single auction (lot) hosted by the contract
+#[cw_serde]
+pub struct AuctionMsg {
+    /// The bid owner, the sender if missing
+    pub owner: Option<String>,
+    /// Receives the proceeds on close, the owner if missing
+    pub beneficiary: Option<String>,
     pub name: String,
+    /// The native denom or cw20 token accepted for bids, tick and commission
+    pub denom: UncheckedDenom,
+    /// Other accepted denoms and their weights converting into `denom`
+    pub weights: Option<Vec<DenomWeight>>,
     pub tick: u128,
-    pub commission: u128,
+    /// Taken from each bid and sent to the fee collector right away
+    pub commission: CommissionModel,
+    /// Receives the commission, the owner if missing
+    pub fee_collector: Option<String>,
+    /// Bids are rejected before this block height or time, open immediately if missing
+    pub start: Option<Scheduled>,
+    /// Bids are rejected after this block height or time, and anyone can close the bid
+    pub end: Option<Expiration>,
+    /// Extend a time based end on late bids, requires `end` to be a time
+    pub extension: Option<Extension>,
+    /// The cw721 contract of the auctioned nft, the bid opens once the owner sends the nft
+    pub nft: Option<String>,
+    /// Closing below the reserve price ends the bid without a winner
+    pub reserve: Option<Reserve>,
+    /// `Reserve::commitment` of a secret reserve price, revealed on close. The owner has
+    /// a day, or 14400 blocks, after the end to reveal it, or the highest bid wins anyway
+    pub reserve_commitment: Option<HexBinary>,
+    /// A bid reaching this price wins and closes the bid right away
+    pub buy_now: Option<BuyNow>,
+    /// Basis points of the highest bid a new bid must outbid it by, at least the tick
+    pub increment_bps: Option<u16>,
+    /// English if missing
+    pub kind: Option<AuctionKind>,
 }
 
-impl InstantiateMsg {
-    pub fn new(name: String, tick: u128, commission: u128) -> Self {
+impl AuctionMsg {
+    pub fn new(name: String, denom: UncheckedDenom, tick: u128, commission: u128) -> Self {
         Self {
+            owner: None,
+            beneficiary: None,
             name,
+            denom,
+            weights: None,
             tick,
-            commission,
+            commission: CommissionModel::flat(commission),
+            fee_collector: None,
+            start: None,
+            end: None,
+            extension: None,
+            nft: None,
+            reserve: None,
+            reserve_commitment: None,
+            buy_now: None,
+            increment_bps: None,
+            kind: None,
         }
     }
 }
 
+/// The secret reserve and salt matching the reserve commitment
+#[cw_serde]
+pub struct Reveal {
+    pub reserve: Uint128,
+    pub salt: Binary,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
-    Bidding {},
-    Close {},
-    Retract { receiver: Option<String> },
+    /// Open a new auction, restricted to the creators when set
+    Open(Box<AuctionMsg>),
+    /// Replace the creators allowed to open auctions, admin only
+    UpdateCreators {
+        creators: Option<Vec<String>>,
+    },
+    Bidding {
+        auction_id: u64,
+    },
+    /// Seal a bid of a sealed bid auction, the deposit must cover the revealed amount
+    Commit {
+        auction_id: u64,
+        hash: HexBinary,
+    },
+    /// Ask a price in a reverse auction, the first ask posts the bond
+    Ask {
+        auction_id: u64,
+        price: Uint128,
+    },
+    /// Bid a unit price for a quantity of a multi-unit auction
+    BidUnits {
+        auction_id: u64,
+        price: Uint128,
+        quantity: u64,
+    },
+    /// Buy a Dutch auction, at least the current price must be sent
+    Buy {
+        auction_id: u64,
+    },
+    /// Reveal the sealed bid after the end, before the reveal end
+    Reveal {
+        auction_id: u64,
+        amount: Uint128,
+        salt: Binary,
+    },
+    /// Close the bid, a committed reserve must be revealed. Once the reveal period after
+    /// the end passes, anyone closes without it and the reserve no longer applies
+    Close {
+        auction_id: u64,
+        reveal: Option<Reveal>,
+    },
+    Retract {
+        auction_id: u64,
+        receiver: Option<String>,
+    },
+    /// Stop accepting bids, owner only
+    Pause {
+        auction_id: u64,
+    },
+    Unpause {
+        auction_id: u64,
+    },
+    /// Cancel the bid before close, owner only. The collected commission must be sent
+    /// along, or through the cw20 hook, so every bidder retracts its full deposit
+    Cancel {
+        auction_id: u64,
+    },
+    /// Void the bid without a winner, every bidder retracts its deposit, owner only
+    EmergencyCancel {
+        auction_id: u64,
+    },
+    /// Propose a new owner, who becomes the owner once it accepts before the expiry
+    ProposeOwner {
+        auction_id: u64,
+        owner: String,
+        expiry: Option<Expiration>,
+    },
+    AcceptOwnership {
+        auction_id: u64,
+    },
+    CancelOwnershipProposal {
+        auction_id: u64,
+    },
+    /// Give up the ownership for good, the proceeds and commission keep going
+    /// to the current beneficiary and fee collector
+    RenounceOwnership {
+        auction_id: u64,
+    },
+    Receive(Cw20ReceiveMsg),
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+/// The hook message sent along with the cw20 tokens
+#[cw_serde]
+pub enum ReceiveMsg {
+    Bid {
+        auction_id: u64,
+    },
+    Commit {
+        auction_id: u64,
+        hash: HexBinary,
+    },
+    Buy {
+        auction_id: u64,
+    },
+    BidUnits {
+        auction_id: u64,
+        price: Uint128,
+        quantity: u64,
+    },
+    Cancel {
+        auction_id: u64,
+    },
+}
+
+/// The hook message sent along with the nft
+#[cw_serde]
+pub enum ReceiveNftMsg {
+    Deposit { auction_id: u64 },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    #[returns(ConfigResp)]
+    Config {},
+    #[returns(AuctionsResp)]
+    Auctions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     #[returns(TotalBidResp)]
-    TotalBid { addr: String },
+    TotalBid { auction_id: u64, addr: String },
     #[returns(HighestOfBidResp)]
-    HighestOfBid {},
+    HighestOfBid { auction_id: u64 },
     #[returns(WinnerResp)]
-    Winner {},
+    Winner { auction_id: u64 },
+    #[returns(StatusResp)]
+    Status { auction_id: u64 },
+    #[returns(DeadlineResp)]
+    Deadline { auction_id: u64 },
+    #[returns(AuctionItemResp)]
+    AuctionItem { auction_id: u64 },
+    #[returns(ReserveResp)]
+    Reserve { auction_id: u64 },
+    /// How much the address must send to become the highest bidder
+    #[returns(MinimumNextBidResp)]
+    MinimumNextBid { auction_id: u64, addr: String },
+    /// The price of a Dutch auction at the current block
+    #[returns(CurrentPriceResp)]
+    CurrentPrice { auction_id: u64 },
+    /// The units the address wins if the multi-unit auction closed now
+    #[returns(UnitAllocationResp)]
+    UnitAllocation { auction_id: u64, addr: String },
+    #[returns(ClearingPriceResp)]
+    ClearingPrice { auction_id: u64 },
+    #[returns(CommitmentResp)]
+    Commitment { auction_id: u64, addr: String },
+    #[returns(BuyNowResp)]
+    BuyNow { auction_id: u64 },
+    #[returns(OwnershipResp)]
+    Ownership { auction_id: u64 },
+    #[returns(CommissionResp)]
+    Commission { auction_id: u64 },
+    /// The commission a bid of `amount` in the bidding denom would pay
+    #[returns(PreviewCommissionResp)]
+    PreviewCommission { auction_id: u64, amount: Uint128 },
+}
+
+#[cw_serde]
+pub struct ConfigResp {
+    pub admin: Addr,
+    pub creators: Option<Vec<Addr>>,
+    // number of auctions opened so far
+    pub auction_count: u64,
+}
+
+#[cw_serde]
+pub struct AuctionResp {
+    pub auction_id: u64,
+    pub name: String,
+    pub owner: Option<Addr>,
+    pub beneficiary: Option<Addr>,
+    pub status: BidStatus,
+}
+
+#[cw_serde]
+pub struct AuctionsResp {
+    pub auctions: Vec<AuctionResp>,
 }
 
 #[cw_serde]
 pub struct TotalBidResp {
+    // the sum of all bids minus all commissions
     pub total: Coin,
+    // the commission paid so far
+    pub commission: Coin,
 }
 
 #[cw_serde]
 pub struct HighestOfBidResp {
     pub bid: Option<Bid>,
+    pub runner_up: Option<Bid>,
+}
+
+#[cw_serde]
+pub struct CurrentPriceResp {
+    pub price: Coin,
+}
+
+#[cw_serde]
+pub struct UnitAllocationResp {
+    pub bid: Option<UnitBid>,
+    pub units: u64,
+    // the lowest allocated unit price, paid by every winner
+    pub clearing_price: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct ClearingPriceResp {
+    // the price charged to the winner, None until closed with a winner
+    pub price: Option<Coin>,
 }
 
 #[cw_serde]
 pub struct WinnerResp {
     pub winner: Option<Addr>,
 }
+
+#[cw_serde]
+pub struct StatusResp {
+    pub status: BidStatus,
+    pub start: Option<Scheduled>,
+    pub end: Option<Expiration>,
+    pub paused: bool,
+    pub emergency: bool,
+}
+
+#[cw_serde]
+pub struct DeadlineResp {
+    pub end: Option<Expiration>,
+    pub hard_cap: Option<Timestamp>,
+    // time or blocks left until the end, zero once it has passed
+    pub remaining: Option<Duration>,
+}
+
+#[cw_serde]
+pub struct AuctionItemResp {
+    pub nft: Option<Addr>,
+    pub item: Option<AuctionItem>,
+}
+
+#[cw_serde]
+pub struct MinimumNextBidResp {
+    // in the bidding denom, commission included
+    pub amount: Coin,
+}
+
+#[cw_serde]
+pub struct CommitmentResp {
+    pub commitment: Option<Commitment>,
+}
+
+#[cw_serde]
+pub struct BuyNowResp {
+    // None once expired
+    pub buy_now: Option<BuyNow>,
+}
+
+#[cw_serde]
+pub struct ReserveResp {
+    pub has_reserve: bool,
+    // the sha256 commitment of a secret reserve
+    pub commitment: Option<HexBinary>,
+    // whether the highest bid reaches the reserve
+    pub met: bool,
+    // the reserve price, None if hidden
+    pub price: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct OwnershipResp {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+    pub pending_expiry: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct CommissionResp {
+    pub model: CommissionModel,
+    pub fee_collector: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct PreviewCommissionResp {
+    pub commission: Coin,
+    // the part of the bid counted towards the total
+    pub net: Coin,
+}
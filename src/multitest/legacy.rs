@@ -0,0 +1,125 @@
+//! A v0.1 bidding contract writing the legacy storage layout, to test migrations
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coin, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw2::set_contract_version;
+use cw_multi_test::{App, ContractWrapper, Executor};
+
+use crate::{
+    state::{v0_1, Bid},
+    ATOM_DENOM,
+};
+
+use super::owner;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub tick: u128,
+    pub commission: u128,
+    // the cw2 contract name and version to store
+    pub contract: String,
+    pub version: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Bidding {},
+    Close {},
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    set_contract_version(deps.storage, msg.contract, msg.version)?;
+
+    let state = v0_1::State {
+        owner: info.sender,
+        name: msg.name,
+        tick: coin(msg.tick, ATOM_DENOM),
+        commission: coin(msg.commission, ATOM_DENOM),
+        status: v0_1::BidStatus::Opening {},
+        highest: None,
+        winner: None,
+    };
+    v0_1::STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Bidding {} => {
+            let mut state = v0_1::STATE.load(deps.storage)?;
+
+            let mut total = v0_1::BIDDINGS
+                .may_load(deps.storage, info.sender.clone())?
+                .unwrap_or_else(|| coin(0, ATOM_DENOM));
+            total.amount += cw_utils::must_pay(&info, ATOM_DENOM)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+            if state
+                .highest
+                .as_ref()
+                .is_some_and(|highest| highest.bid.amount >= total.amount)
+            {
+                return Err(StdError::generic_err("bid too low"));
+            }
+
+            state.highest = Some(Bid {
+                bid: total.clone(),
+                bidder: info.sender.clone(),
+            });
+
+            v0_1::STATE.save(deps.storage, &state)?;
+            v0_1::BIDDINGS.save(deps.storage, info.sender, &total)?;
+
+            Ok(Response::new())
+        }
+        // the deposits stay in storage after close, as the legacy retract left them
+        ExecuteMsg::Close {} => {
+            let mut state = v0_1::STATE.load(deps.storage)?;
+
+            state.status = v0_1::BidStatus::Closed {};
+            state.winner = state.highest.as_ref().map(|highest| highest.bidder.clone());
+            v0_1::STATE.save(deps.storage, &state)?;
+
+            Ok(Response::new())
+        }
+    }
+}
+
+fn query(_deps: Deps, _env: Env, _msg: ExecuteMsg) -> StdResult<Binary> {
+    Err(StdError::generic_err("not supported"))
+}
+
+pub fn store_code(app: &mut App) -> u64 {
+    let contract = ContractWrapper::new(execute, instantiate, query);
+    app.store_code(Box::new(contract))
+}
+
+/// Instantiate a legacy auction with the owner as its admin
+pub fn instantiate_legacy(app: &mut App, code_id: u64, contract: &str, version: &str) -> Addr {
+    let msg = InstantiateMsg {
+        name: "bidding".into(),
+        tick: 1,
+        commission: 1,
+        contract: contract.into(),
+        version: version.into(),
+    };
+
+    app.instantiate_contract(
+        code_id,
+        owner(),
+        &msg,
+        &[],
+        "legacy",
+        Some(owner().to_string()),
+    )
+    .unwrap()
+}
@@ -1,31 +1,63 @@
+pub mod legacy;
+#[cfg(test)]
 mod tests;
 
-use cosmwasm_std::{Addr, Coin, StdResult};
+use cosmwasm_std::{from_json, to_json_binary, Addr, Coin, Empty, HexBinary, StdResult, Uint128};
+use cw20::{Cw20Coin, Cw20ExecuteMsg, UncheckedDenom};
 use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
+use cw_utils::Expiration;
 
 use crate::{
     contract::instantiate,
-    execute,
-    msg::{ExecuteMsg, HighestOfBidResp, InstantiateMsg, QueryMsg, TotalBidResp, WinnerResp},
-    query, ContractError, CONTRACT_LABEL,
+    execute, migrate,
+    msg::{
+        AuctionItemResp, AuctionMsg, AuctionsResp, BuyNowResp, ClearingPriceResp, CommissionResp,
+        CommitmentResp, ConfigResp, CurrentPriceResp, DeadlineResp, ExecuteMsg, HighestOfBidResp,
+        InstantiateMsg, MigrateMsg, MinimumNextBidResp, OwnershipResp, PreviewCommissionResp,
+        QueryMsg, ReceiveMsg, ReceiveNftMsg, ReserveResp, Reveal, StatusResp, TotalBidResp,
+        UnitAllocationResp, WinnerResp,
+    },
+    query, ContractError, ATOM_DENOM, CONTRACT_LABEL,
 };
 
-pub struct BiddingContract(Addr);
+/// An auction hosted by the bidding contract
+#[derive(Debug)]
+pub struct BiddingContract {
+    addr: Addr,
+    auction_id: u64,
+}
 
 impl BiddingContract {
-    pub fn new(owner: Addr) -> Self {
-        Self(owner)
+    pub fn new(addr: Addr, auction_id: u64) -> Self {
+        Self { addr, auction_id }
     }
 
     pub fn addr(&self) -> Addr {
-        self.0.clone()
+        self.addr.clone()
+    }
+
+    pub fn auction_id(&self) -> u64 {
+        self.auction_id
     }
 
     pub fn store_code(app: &mut App) -> u64 {
-        let contract = ContractWrapper::new(execute, instantiate, query);
+        let contract = ContractWrapper::new(execute, instantiate, query).with_migrate(migrate);
         app.store_code(Box::new(contract))
     }
 
+    /// Migrate the contract to the code id, returns its first auction
+    #[track_caller]
+    pub fn migrate(
+        app: &mut App,
+        sender: Addr,
+        addr: &Addr,
+        code_id: u64,
+    ) -> Result<BiddingContract, ContractError> {
+        app.migrate_contract(sender, addr.clone(), &MigrateMsg {}, code_id)
+            .map_err(|e| e.downcast().unwrap())
+            .map(|_| BiddingContract::new(addr.clone(), 1))
+    }
+
     #[track_caller]
     pub fn instantiate(
         app: &mut App,
@@ -35,20 +67,87 @@ impl BiddingContract {
         tick: u128,
         commission: u128,
     ) -> StdResult<BiddingContract> {
-        app.instantiate_contract(
+        Self::instantiate_with_msg(
+            app,
             code_id,
             sender,
-            &InstantiateMsg {
-                name: name.into(),
+            &AuctionMsg::new(
+                name.into(),
+                UncheckedDenom::Native(ATOM_DENOM.into()),
                 tick,
                 commission,
-            },
+            ),
+        )
+        .map_err(|e| match e {
+            ContractError::Std(e) => e,
+            e => panic!("unexpected error: {e}"),
+        })
+    }
+
+    #[track_caller]
+    pub fn instantiate_with_msg(
+        app: &mut App,
+        code_id: u64,
+        sender: Addr,
+        msg: &AuctionMsg,
+    ) -> Result<BiddingContract, ContractError> {
+        Self::instantiate_with_funds(app, code_id, sender, msg, &[])
+    }
+
+    #[track_caller]
+    pub fn instantiate_with_funds(
+        app: &mut App,
+        code_id: u64,
+        sender: Addr,
+        msg: &AuctionMsg,
+        send_funds: &[Coin],
+    ) -> Result<BiddingContract, ContractError> {
+        let msg = InstantiateMsg {
+            creators: None,
+            auction: Some(Box::new(msg.clone())),
+        };
+
+        app.instantiate_contract(code_id, sender, &msg, send_funds, CONTRACT_LABEL, None)
+            .map_err(|e| e.downcast().unwrap())
+            .map(|addr| BiddingContract::new(addr, 1))
+    }
+
+    /// Open another auction in the contract hosting this one
+    #[track_caller]
+    pub fn open(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        msg: &AuctionMsg,
+    ) -> Result<BiddingContract, ContractError> {
+        let resp = app
+            .execute_contract(
+                sender,
+                self.addr(),
+                &ExecuteMsg::Open(Box::new(msg.clone())),
+                &[],
+            )
+            .map_err(|e| e.downcast::<ContractError>().unwrap())?;
+
+        let auction_id = from_json(resp.data.unwrap()).unwrap();
+
+        Ok(BiddingContract::new(self.addr(), auction_id))
+    }
+
+    #[track_caller]
+    pub fn update_creators(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        creators: Option<Vec<String>>,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::UpdateCreators { creators },
             &[],
-            CONTRACT_LABEL,
-            None,
         )
         .map_err(|e| e.downcast().unwrap())
-        .map(BiddingContract)
     }
 
     #[track_caller]
@@ -58,16 +157,184 @@ impl BiddingContract {
         sender: Addr,
         send_funds: &[Coin],
     ) -> Result<AppResponse, ContractError> {
-        app.execute_contract(sender, self.addr(), &ExecuteMsg::Bidding {}, send_funds)
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Bidding {
+                auction_id: self.auction_id,
+            },
+            send_funds,
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn bid_cw20(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        cw20: &Addr,
+        amount: u128,
+    ) -> Result<AppResponse, ContractError> {
+        let msg = Cw20ExecuteMsg::Send {
+            contract: self.addr().to_string(),
+            amount: amount.into(),
+            msg: to_json_binary(&ReceiveMsg::Bid {
+                auction_id: self.auction_id,
+            })
+            .unwrap(),
+        };
+
+        app.execute_contract(sender, cw20.clone(), &msg, &[])
             .map_err(|e| e.downcast().unwrap())
     }
 
     #[track_caller]
-    pub fn close(&self, app: &mut App, sender: Addr) -> Result<AppResponse, ContractError> {
-        app.execute_contract(sender, self.addr(), &ExecuteMsg::Close {}, &[])
+    pub fn ask(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        price: u128,
+        send_funds: &[Coin],
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Ask {
+                auction_id: self.auction_id,
+                price: Uint128::new(price),
+            },
+            send_funds,
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn bid_units(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        price: u128,
+        quantity: u64,
+        send_funds: &[Coin],
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::BidUnits {
+                auction_id: self.auction_id,
+                price: Uint128::new(price),
+                quantity,
+            },
+            send_funds,
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn buy(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        send_funds: &[Coin],
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Buy {
+                auction_id: self.auction_id,
+            },
+            send_funds,
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn commit(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        hash: HexBinary,
+        send_funds: &[Coin],
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Commit {
+                auction_id: self.auction_id,
+                hash,
+            },
+            send_funds,
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn reveal(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        amount: u128,
+        salt: &[u8],
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Reveal {
+                auction_id: self.auction_id,
+                amount: Uint128::new(amount),
+                salt: salt.into(),
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn deposit_nft(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        nft: &Addr,
+        token_id: &str,
+    ) -> Result<AppResponse, ContractError> {
+        let msg = cw721_base::ExecuteMsg::<cw721_base::Extension, Empty>::SendNft {
+            contract: self.addr().to_string(),
+            token_id: token_id.into(),
+            msg: to_json_binary(&ReceiveNftMsg::Deposit {
+                auction_id: self.auction_id,
+            })
+            .unwrap(),
+        };
+
+        app.execute_contract(sender, nft.clone(), &msg, &[])
             .map_err(|e| e.downcast().unwrap())
     }
 
+    #[track_caller]
+    pub fn close(&self, app: &mut App, sender: Addr) -> Result<AppResponse, ContractError> {
+        self.close_with_reveal(app, sender, None)
+    }
+
+    #[track_caller]
+    pub fn close_with_reveal(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        reveal: Option<Reveal>,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Close {
+                auction_id: self.auction_id,
+                reveal,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
     #[track_caller]
     pub fn retract(
         &self,
@@ -75,24 +342,340 @@ impl BiddingContract {
         sender: Addr,
         receiver: Option<String>,
     ) -> Result<AppResponse, ContractError> {
-        app.execute_contract(sender, self.addr(), &ExecuteMsg::Retract { receiver }, &[])
-            .map_err(|e| e.downcast().unwrap())
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Retract {
+                auction_id: self.auction_id,
+                receiver,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
     }
 
     pub fn query_total_bid(&self, app: &App, addr: String) -> Result<TotalBidResp, ContractError> {
         app.wrap()
-            .query_wasm_smart(self.addr(), &QueryMsg::TotalBid { addr })
+            .query_wasm_smart(
+                self.addr(),
+                &QueryMsg::TotalBid {
+                    auction_id: self.auction_id,
+                    addr,
+                },
+            )
             .map_err(ContractError::Std)
     }
 
     pub fn query_winner(&self, app: &App) -> StdResult<WinnerResp> {
-        app.wrap()
-            .query_wasm_smart(self.addr(), &QueryMsg::Winner {})
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Winner {
+                auction_id: self.auction_id,
+            },
+        )
     }
 
     pub fn query_highest_of_bid(&self, app: &App) -> StdResult<HighestOfBidResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::HighestOfBid {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_status(&self, app: &App) -> StdResult<StatusResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Status {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_deadline(&self, app: &App) -> StdResult<DeadlineResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Deadline {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_auction_item(&self, app: &App) -> StdResult<AuctionItemResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::AuctionItem {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn pause(&self, app: &mut App, sender: Addr) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Pause {
+                auction_id: self.auction_id,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn unpause(&self, app: &mut App, sender: Addr) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Unpause {
+                auction_id: self.auction_id,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn cancel(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        funds: &[Coin],
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::Cancel {
+                auction_id: self.auction_id,
+            },
+            funds,
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn cancel_cw20(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        cw20: &Addr,
+        amount: u128,
+    ) -> Result<AppResponse, ContractError> {
+        let msg = Cw20ExecuteMsg::Send {
+            contract: self.addr().to_string(),
+            amount: amount.into(),
+            msg: to_json_binary(&ReceiveMsg::Cancel {
+                auction_id: self.auction_id,
+            })
+            .unwrap(),
+        };
+
+        app.execute_contract(sender, cw20.clone(), &msg, &[])
+            .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn emergency_cancel(
+        &self,
+        app: &mut App,
+        sender: Addr,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::EmergencyCancel {
+                auction_id: self.auction_id,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn propose_owner(
+        &self,
+        app: &mut App,
+        sender: Addr,
+        owner: &Addr,
+        expiry: Option<Expiration>,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::ProposeOwner {
+                auction_id: self.auction_id,
+                owner: owner.to_string(),
+                expiry,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn accept_ownership(
+        &self,
+        app: &mut App,
+        sender: Addr,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::AcceptOwnership {
+                auction_id: self.auction_id,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn cancel_ownership_proposal(
+        &self,
+        app: &mut App,
+        sender: Addr,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::CancelOwnershipProposal {
+                auction_id: self.auction_id,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn renounce_ownership(
+        &self,
+        app: &mut App,
+        sender: Addr,
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(
+            sender,
+            self.addr(),
+            &ExecuteMsg::RenounceOwnership {
+                auction_id: self.auction_id,
+            },
+            &[],
+        )
+        .map_err(|e| e.downcast().unwrap())
+    }
+
+    pub fn query_minimum_next_bid(&self, app: &App, addr: Addr) -> StdResult<MinimumNextBidResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::MinimumNextBid {
+                auction_id: self.auction_id,
+                addr: addr.into_string(),
+            },
+        )
+    }
+
+    pub fn query_current_price(&self, app: &App) -> StdResult<CurrentPriceResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::CurrentPrice {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_unit_allocation(&self, app: &App, addr: Addr) -> StdResult<UnitAllocationResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::UnitAllocation {
+                auction_id: self.auction_id,
+                addr: addr.into_string(),
+            },
+        )
+    }
+
+    pub fn query_clearing_price(&self, app: &App) -> StdResult<ClearingPriceResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::ClearingPrice {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_commitment(&self, app: &App, addr: Addr) -> StdResult<CommitmentResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Commitment {
+                auction_id: self.auction_id,
+                addr: addr.into_string(),
+            },
+        )
+    }
+
+    pub fn query_buy_now(&self, app: &App) -> StdResult<BuyNowResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::BuyNow {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_reserve(&self, app: &App) -> StdResult<ReserveResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Reserve {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_ownership(&self, app: &App) -> StdResult<OwnershipResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Ownership {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_commission(&self, app: &App) -> StdResult<CommissionResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Commission {
+                auction_id: self.auction_id,
+            },
+        )
+    }
+
+    pub fn query_preview_commission(
+        &self,
+        app: &App,
+        amount: u128,
+    ) -> StdResult<PreviewCommissionResp> {
+        app.wrap().query_wasm_smart(
+            self.addr(),
+            &QueryMsg::PreviewCommission {
+                auction_id: self.auction_id,
+                amount: amount.into(),
+            },
+        )
+    }
+
+    pub fn query_config(&self, app: &App) -> StdResult<ConfigResp> {
+        app.wrap()
+            .query_wasm_smart(self.addr(), &QueryMsg::Config {})
+    }
+
+    pub fn query_auctions(
+        &self,
+        app: &App,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<AuctionsResp> {
         app.wrap()
-            .query_wasm_smart(self.addr(), &QueryMsg::HighestOfBid {})
+            .query_wasm_smart(self.addr(), &QueryMsg::Auctions { start_after, limit })
     }
 
     pub fn query_balance(&self, app: &App, denom: impl Into<String>) -> StdResult<Coin> {
@@ -100,6 +683,94 @@ impl BiddingContract {
     }
 }
 
+pub fn instantiate_cw20(app: &mut App, balances: &[(Addr, u128)]) -> Addr {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    let code_id = app.store_code(Box::new(contract));
+
+    let msg = cw20_base::msg::InstantiateMsg {
+        name: "Bidding Token".into(),
+        symbol: "BIDT".into(),
+        decimals: 6,
+        initial_balances: balances
+            .iter()
+            .map(|(address, amount)| Cw20Coin {
+                address: address.to_string(),
+                amount: (*amount).into(),
+            })
+            .collect(),
+        mint: None,
+        marketing: None,
+    };
+
+    app.instantiate_contract(code_id, owner(), &msg, &[], "cw20", None)
+        .unwrap()
+}
+
+pub fn query_cw20_balance(app: &App, cw20: &Addr, addr: &Addr) -> u128 {
+    let resp: cw20::BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            cw20,
+            &cw20::Cw20QueryMsg::Balance {
+                address: addr.to_string(),
+            },
+        )
+        .unwrap();
+
+    resp.balance.u128()
+}
+
+/// Instantiate a cw721 contract and mint the tokens to their owners
+pub fn instantiate_cw721(app: &mut App, tokens: &[(&str, Addr)]) -> Addr {
+    let contract = ContractWrapper::new(
+        cw721_base::entry::execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    );
+    let code_id = app.store_code(Box::new(contract));
+
+    let msg = cw721_base::InstantiateMsg {
+        name: "Bidding Nft".into(),
+        symbol: "BIDN".into(),
+        minter: parent().to_string(),
+    };
+    let nft = app
+        .instantiate_contract(code_id, parent(), &msg, &[], "cw721", None)
+        .unwrap();
+
+    for (token_id, owner) in tokens {
+        let msg = cw721_base::ExecuteMsg::<cw721_base::Extension, Empty>::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        app.execute_contract(parent(), nft.clone(), &msg, &[])
+            .unwrap();
+    }
+
+    nft
+}
+
+pub fn query_nft_owner(app: &App, nft: &Addr, token_id: &str) -> Addr {
+    let resp: cw721::OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            nft,
+            &cw721_base::QueryMsg::<Empty>::OwnerOf {
+                token_id: token_id.into(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+
+    Addr::unchecked(resp.owner)
+}
+
 pub fn alice() -> Addr {
     Addr::unchecked("sei18rszd3tmgpjvjwq2qajtmn5jqvtscd2yuygl4z")
 }
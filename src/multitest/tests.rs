@@ -1,14 +1,23 @@
-use cosmwasm_std::{coin, coins};
-use cw_multi_test::App;
+use cosmwasm_std::{coin, coins, Decimal, Uint128};
+use cw20::UncheckedDenom;
+use cw_multi_test::{App, Executor};
+use cw_utils::{Duration, Expiration, PaymentError, Scheduled};
 
 use crate::{
     helper::{ten_atom, zero_atom},
-    multitest::{alice, owner},
-    state::Bid,
+    msg::{AuctionMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, Reveal},
+    multitest::{
+        alice, instantiate_cw20, instantiate_cw721, owner, parent, query_cw20_balance,
+        query_nft_owner,
+    },
+    state::{
+        commitment, AuctionItem, AuctionKind, Bid, BidStatus, BuyNow, CommissionModel,
+        CommissionTier, Decay, DenomWeight, Extension, Reserve, RESERVE_REVEAL_BLOCKS,
+    },
     ContractError, ATOM_DENOM,
 };
 
-use super::{bob, BiddingContract};
+use super::{bob, legacy, BiddingContract};
 
 #[test]
 fn query_total_bid_should_works() {
@@ -70,7 +79,7 @@ fn bid_should_works() {
     assert_eq!(
         highest.bid,
         Some(Bid {
-            bid: coin(2, ATOM_DENOM),
+            bid: coin(1, ATOM_DENOM),
             bidder: alice()
         })
     );
@@ -85,7 +94,8 @@ fn bid_should_works() {
     assert_eq!(
         err,
         ContractError::BidTooLowErr {
-            less_than: coin(2, ATOM_DENOM)
+            total: coin(1, ATOM_DENOM),
+            minimum: coin(2, ATOM_DENOM)
         }
     );
 
@@ -101,21 +111,21 @@ fn bid_should_works() {
     assert_eq!(
         highest.bid,
         Some(Bid {
-            bid: coin(3, ATOM_DENOM),
+            bid: coin(2, ATOM_DENOM),
             bidder: bob()
         })
     );
 
     // bob bid add amount
     contract
-        .bid(&mut app, bob(), &coins(1, ATOM_DENOM))
+        .bid(&mut app, bob(), &coins(2, ATOM_DENOM))
         .unwrap();
 
     let highest = contract.query_highest_of_bid(&app).unwrap();
     assert_eq!(
         highest.bid,
         Some(Bid {
-            bid: coin(4, ATOM_DENOM),
+            bid: coin(3, ATOM_DENOM),
             bidder: bob()
         })
     );
@@ -127,28 +137,35 @@ fn bid_should_works() {
     assert_eq!(
         err,
         ContractError::BidTooLowErr {
-            less_than: coin(4, ATOM_DENOM)
+            total: coin(2, ATOM_DENOM),
+            minimum: coin(4, ATOM_DENOM)
         }
     );
 
     contract
-        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .bid(&mut app, alice(), &coins(4, ATOM_DENOM))
         .unwrap();
 
     let highest = contract.query_highest_of_bid(&app).unwrap();
     assert_eq!(
         highest.bid,
         Some(Bid {
-            bid: coin(5, ATOM_DENOM),
+            bid: coin(4, ATOM_DENOM),
             bidder: alice()
         })
     );
 
+    // the commission of each bid is tracked apart from the total
     let alice_total = contract.query_total_bid(&app, alice().to_string()).unwrap();
     assert_eq!(alice_total.total, coin(4, ATOM_DENOM));
+    assert_eq!(alice_total.commission, coin(2, ATOM_DENOM));
 
     let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
-    assert_eq!(balance, coin(9, ATOM_DENOM));
+    assert_eq!(balance, coin(7, ATOM_DENOM));
+
+    // the commission was sent to the owner on each bid
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(4, ATOM_DENOM));
 }
 
 #[test]
@@ -187,26 +204,29 @@ fn close_bid_retract_should_works() {
     assert_eq!(
         highest.bid,
         Some(Bid {
-            bid: coin(5, ATOM_DENOM),
+            bid: coin(3, ATOM_DENOM),
             bidder: alice()
         })
     );
 
     // check the contract total and balance
     let alice_total = contract.query_total_bid(&app, alice().to_string()).unwrap();
-    assert_eq!(alice_total.total, coin(4, ATOM_DENOM));
+    assert_eq!(alice_total.total, coin(3, ATOM_DENOM));
 
     let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
-    assert_eq!(balance, coin(8, ATOM_DENOM));
+    assert_eq!(balance, coin(5, ATOM_DENOM));
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(3, ATOM_DENOM));
 
     // owner close the bid
     contract.close(&mut app, owner()).unwrap();
 
     let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
-    assert_eq!(balance, coin(3, ATOM_DENOM));
+    assert_eq!(balance, coin(2, ATOM_DENOM));
 
     let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
-    assert_eq!(owner_balance, coin(5, ATOM_DENOM));
+    assert_eq!(owner_balance, coin(6, ATOM_DENOM));
 
     // retract funds
     contract.retract(&mut app, bob(), None).unwrap();
@@ -230,3 +250,3117 @@ fn close_not_owner_should_fails() {
     let err = contract.close(&mut app, alice()).unwrap_err();
     assert_eq!(err, ContractError::Unauthorized {})
 }
+
+#[test]
+fn bid_outside_schedule_should_fails() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let now = app.block_info().time;
+    let msg = AuctionMsg {
+        start: Some(Scheduled::AtTime(now.plus_seconds(100))),
+        end: Some(Expiration::AtTime(now.plus_seconds(200))),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let status = contract.query_status(&app).unwrap();
+    assert_eq!(status.status, BidStatus::NotStarted {});
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidNotStarted {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let status = contract.query_status(&app).unwrap();
+    assert_eq!(status.status, BidStatus::Opening {});
+
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyEnded {});
+}
+
+#[test]
+fn close_after_end_by_anyone_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let end = app.block_info().height + 10;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtHeight(end)),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    // the owner can't close before the end
+    let err = contract.close(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::BidIsOpening {});
+
+    app.update_block(|block| block.height = end);
+
+    // anyone can close after the end, the proceeds go to the owner
+    contract.close(&mut app, bob()).unwrap();
+
+    let winner = contract.query_winner(&app).unwrap();
+    assert_eq!(winner.winner, Some(alice()));
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(2, ATOM_DENOM));
+
+    let status = contract.query_status(&app).unwrap();
+    assert_eq!(status.status, BidStatus::Closed {});
+
+    let err = contract.close(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyClosed {});
+}
+
+#[test]
+fn instantiate_invalid_schedule_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let height = app.block_info().height;
+    let msg = AuctionMsg {
+        start: Some(Scheduled::AtHeight(height + 10)),
+        end: Some(Expiration::AtHeight(height + 5)),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidScheduleErr {
+            reason: "start must be before end".into()
+        }
+    );
+}
+
+#[test]
+fn late_bid_extends_end_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let now = app.block_info().time;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtTime(now.plus_seconds(100))),
+        extension: Some(Extension {
+            window: 10,
+            duration: 30,
+            hard_cap: Some(now.plus_seconds(150)),
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    // a bid outside of the window doesn't extend the end
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    let deadline = contract.query_deadline(&app).unwrap();
+    assert_eq!(
+        deadline.end,
+        Some(Expiration::AtTime(now.plus_seconds(100)))
+    );
+    assert_eq!(deadline.remaining, Some(Duration::Time(100)));
+
+    // a bid within the window extends the end
+    app.update_block(|block| block.time = block.time.plus_seconds(95));
+
+    let resp = contract
+        .bid(&mut app, bob(), &coins(3, ATOM_DENOM))
+        .unwrap();
+    let end = now.plus_seconds(130).seconds().to_string();
+    assert!(resp
+        .events
+        .iter()
+        .flat_map(|e| &e.attributes)
+        .any(|a| a.key == "end" && a.value == end));
+
+    let deadline = contract.query_deadline(&app).unwrap();
+    assert_eq!(
+        deadline.end,
+        Some(Expiration::AtTime(now.plus_seconds(130)))
+    );
+    assert_eq!(deadline.hard_cap, Some(now.plus_seconds(150)));
+    assert_eq!(deadline.remaining, Some(Duration::Time(35)));
+
+    // the extension never passes the hard cap
+    app.update_block(|block| block.time = block.time.plus_seconds(30));
+
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+
+    let deadline = contract.query_deadline(&app).unwrap();
+    assert_eq!(
+        deadline.end,
+        Some(Expiration::AtTime(now.plus_seconds(150)))
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(25));
+
+    let err = contract
+        .bid(&mut app, bob(), &coins(3, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyEnded {});
+}
+
+#[test]
+fn extension_without_time_end_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let height = app.block_info().height;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtHeight(height + 10)),
+        extension: Some(Extension {
+            window: 10,
+            duration: 30,
+            hard_cap: None,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidExtensionErr {
+            reason: "end must be a time".into()
+        }
+    );
+}
+
+#[test]
+fn bid_with_configured_denom_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &alice(),
+                vec![coin(10, "usei"), coin(10, ATOM_DENOM)],
+            )
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg::new(
+        "bidding".into(),
+        UncheckedDenom::Native("usei".into()),
+        1,
+        1,
+    );
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CoinSupportedOnlyErr {
+            denom: ATOM_DENOM.into()
+        }
+    );
+
+    contract.bid(&mut app, alice(), &coins(2, "usei")).unwrap();
+
+    let alice_total = contract.query_total_bid(&app, alice().to_string()).unwrap();
+    assert_eq!(alice_total.total, coin(1, "usei"));
+
+    contract.close(&mut app, owner()).unwrap();
+
+    let owner_balance = app.wrap().query_balance(owner(), "usei").unwrap();
+    assert_eq!(owner_balance, coin(2, "usei"));
+}
+
+#[test]
+fn instantiate_invalid_denom_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    for denom in ["", "u", "1atom", "u atom"] {
+        let msg = AuctionMsg::new("bidding".into(), UncheckedDenom::Native(denom.into()), 1, 1);
+        let err =
+            BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDenomErr {
+                denom: denom.into()
+            }
+        );
+    }
+
+    let msg = AuctionMsg::new(
+        "bidding".into(),
+        UncheckedDenom::Native(
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".into(),
+        ),
+        1,
+        1,
+    );
+    BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+}
+
+#[test]
+fn bid_with_weighted_denoms_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &alice(),
+                vec![coin(10, "uinj"), coin(10, ATOM_DENOM), coin(10, "usei")],
+            )
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    // one uinj is worth two ATOM
+    let msg = AuctionMsg {
+        weights: Some(vec![DenomWeight {
+            denom: "uinj".into(),
+            weight: Decimal::percent(200),
+        }]),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            3,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(5, "usei"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CoinSupportedOnlyErr {
+            denom: "usei".into()
+        }
+    );
+
+    // the commission of 3 ATOM is charged as 2 uinj, rounding up
+    contract.bid(&mut app, alice(), &coins(3, "uinj")).unwrap();
+
+    let highest = contract.query_highest_of_bid(&app).unwrap();
+    assert_eq!(
+        highest.bid,
+        Some(Bid {
+            bid: coin(2, ATOM_DENOM),
+            bidder: alice()
+        })
+    );
+
+    let err = contract
+        .bid(&mut app, bob(), &coins(4, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BidTooLowErr {
+            total: coin(1, ATOM_DENOM),
+            minimum: coin(3, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .bid(&mut app, bob(), &coins(6, ATOM_DENOM))
+        .unwrap();
+
+    // alice tops up with the bidding denom
+    contract
+        .bid(&mut app, alice(), &[coin(1, "uinj"), coin(3, ATOM_DENOM)])
+        .unwrap();
+
+    let alice_total = contract.query_total_bid(&app, alice().to_string()).unwrap();
+    assert_eq!(alice_total.total, coin(4, ATOM_DENOM));
+    assert_eq!(alice_total.commission, coin(7, ATOM_DENOM));
+
+    // the owner receives the commissions and the winner bids in the deposited denoms
+    contract.close(&mut app, owner()).unwrap();
+
+    let owner_balances = app.wrap().query_all_balances(owner()).unwrap();
+    assert_eq!(owner_balances, vec![coin(6, ATOM_DENOM), coin(4, "uinj")]);
+
+    // bob retracts his bid minus the commission
+    contract.retract(&mut app, bob(), None).unwrap();
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(7, ATOM_DENOM));
+
+    // and can't retract twice
+    contract.retract(&mut app, bob(), None).unwrap();
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(7, ATOM_DENOM));
+}
+
+#[test]
+fn instantiate_invalid_weights_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        weights: Some(vec![DenomWeight {
+            denom: "uinj".into(),
+            weight: Decimal::zero(),
+        }]),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidDenomErr {
+            denom: "uinj".into()
+        }
+    );
+}
+
+#[test]
+fn bid_with_cw20_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let cw20 = instantiate_cw20(&mut app, &[(alice(), 10), (bob(), 10)]);
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg::new(
+        "bidding".into(),
+        UncheckedDenom::Cw20(cw20.to_string()),
+        1,
+        1,
+    );
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    // native coins are not accepted
+    let err = contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CoinSupportedOnlyErr {
+            denom: ATOM_DENOM.into()
+        }
+    );
+
+    contract.bid_cw20(&mut app, alice(), &cw20, 2).unwrap();
+    contract.bid_cw20(&mut app, bob(), &cw20, 3).unwrap();
+
+    let highest = contract.query_highest_of_bid(&app).unwrap();
+    assert_eq!(
+        highest.bid,
+        Some(Bid {
+            bid: coin(2, cw20.as_str()),
+            bidder: bob()
+        })
+    );
+    assert_eq!(query_cw20_balance(&app, &cw20, &contract.addr()), 3);
+    assert_eq!(query_cw20_balance(&app, &cw20, &owner()), 2);
+
+    contract.close(&mut app, owner()).unwrap();
+    assert_eq!(query_cw20_balance(&app, &cw20, &owner()), 4);
+
+    // alice retracts her bid minus the commission to bob
+    contract
+        .retract(&mut app, alice(), Some(bob().to_string()))
+        .unwrap();
+    assert_eq!(query_cw20_balance(&app, &cw20, &bob()), 8);
+    assert_eq!(query_cw20_balance(&app, &cw20, &contract.addr()), 0);
+}
+
+#[test]
+fn bid_with_other_cw20_should_fails() {
+    let mut app = App::default();
+
+    let cw20 = instantiate_cw20(&mut app, &[(alice(), 10)]);
+    let other = instantiate_cw20(&mut app, &[(alice(), 10)]);
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg::new(
+        "bidding".into(),
+        UncheckedDenom::Cw20(cw20.to_string()),
+        1,
+        1,
+    );
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let err = contract.bid_cw20(&mut app, alice(), &other, 2).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CoinSupportedOnlyErr {
+            denom: other.to_string()
+        }
+    );
+}
+
+#[test]
+fn nft_delivered_to_winner_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let nft = instantiate_cw721(&mut app, &[("punk", owner()), ("ape", alice())]);
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        nft: Some(nft.to_string()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    // no bids before the nft is deposited
+    let err = contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidNotStarted {});
+
+    // only the owner can deposit the nft
+    let err = contract
+        .deposit_nft(&mut app, alice(), &nft, "ape")
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract
+        .deposit_nft(&mut app, owner(), &nft, "punk")
+        .unwrap();
+
+    let resp = contract.query_auction_item(&app).unwrap();
+    assert_eq!(
+        resp.item,
+        Some(AuctionItem {
+            contract: nft.clone(),
+            token_id: "punk".into()
+        })
+    );
+    assert_eq!(query_nft_owner(&app, &nft, "punk"), contract.addr());
+
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    contract.close(&mut app, owner()).unwrap();
+
+    assert_eq!(query_nft_owner(&app, &nft, "punk"), alice());
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(2, ATOM_DENOM));
+}
+
+#[test]
+fn nft_returned_to_owner_without_bids_should_works() {
+    let mut app = App::default();
+
+    let nft = instantiate_cw721(&mut app, &[("punk", owner())]);
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        nft: Some(nft.to_string()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .deposit_nft(&mut app, owner(), &nft, "punk")
+        .unwrap();
+    assert_eq!(query_nft_owner(&app, &nft, "punk"), contract.addr());
+
+    contract.close(&mut app, owner()).unwrap();
+
+    assert_eq!(query_nft_owner(&app, &nft, "punk"), owner());
+}
+
+#[test]
+fn multiple_auctions_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let first = BiddingContract::instantiate(&mut app, code_id, owner(), "first", 1, 1).unwrap();
+
+    let second = first
+        .open(
+            &mut app,
+            bob(),
+            &AuctionMsg::new(
+                "second".into(),
+                UncheckedDenom::Native(ATOM_DENOM.into()),
+                1,
+                1,
+            ),
+        )
+        .unwrap();
+    assert_eq!(second.auction_id(), 2);
+
+    first.bid(&mut app, alice(), &coins(3, ATOM_DENOM)).unwrap();
+    second
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    // bids are tracked per auction
+    let resp = first.query_total_bid(&app, alice().to_string()).unwrap();
+    assert_eq!(resp.total, coin(2, ATOM_DENOM));
+    let resp = second.query_total_bid(&app, alice().to_string()).unwrap();
+    assert_eq!(resp.total, coin(1, ATOM_DENOM));
+
+    // each auction is closed by its own owner
+    let err = second.close(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    second.close(&mut app, bob()).unwrap();
+
+    assert_eq!(second.query_winner(&app).unwrap().winner, Some(alice()));
+    assert_eq!(first.query_winner(&app).unwrap().winner, None);
+    assert_eq!(
+        first.query_status(&app).unwrap().status,
+        BidStatus::Opening {}
+    );
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(12, ATOM_DENOM));
+
+    let resp = first.query_auctions(&app, None, None).unwrap();
+    assert_eq!(resp.auctions.len(), 2);
+    assert_eq!(resp.auctions[1].name, "second");
+    assert_eq!(resp.auctions[1].owner, Some(bob()));
+    assert_eq!(resp.auctions[1].status, BidStatus::Closed {});
+
+    let resp = first.query_auctions(&app, Some(1), None).unwrap();
+    assert_eq!(resp.auctions.len(), 1);
+    assert_eq!(resp.auctions[0].auction_id, 2);
+}
+
+#[test]
+fn open_auction_not_creator_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let contract =
+        BiddingContract::instantiate(&mut app, code_id, owner(), "bidding", 1, 1).unwrap();
+
+    let msg = AuctionMsg::new(
+        "lot".into(),
+        UncheckedDenom::Native(ATOM_DENOM.into()),
+        1,
+        1,
+    );
+
+    // only the admin updates the creators
+    let err = contract
+        .update_creators(&mut app, alice(), Some(vec![]))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract
+        .update_creators(&mut app, owner(), Some(vec![bob().to_string()]))
+        .unwrap();
+
+    let err = contract.open(&mut app, alice(), &msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract.open(&mut app, bob(), &msg).unwrap();
+
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.admin, owner());
+    assert_eq!(resp.creators, Some(vec![bob()]));
+    assert_eq!(resp.auction_count, 2);
+}
+
+#[test]
+fn commission_sent_to_fee_collector_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        fee_collector: Some(parent().to_string()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            2,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    // the bid must cover the commission
+    let err = contract
+        .bid(&mut app, alice(), &coins(1, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBidErr {
+            total_bid: coin(1, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid(&mut app, alice(), &coins(4, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_total_bid(&app, alice().to_string()).unwrap();
+    assert_eq!(resp.total, coin(3, ATOM_DENOM));
+    assert_eq!(resp.commission, coin(4, ATOM_DENOM));
+
+    let collector_balance = app.wrap().query_balance(parent(), ATOM_DENOM).unwrap();
+    assert_eq!(collector_balance, coin(4, ATOM_DENOM));
+
+    let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
+    assert_eq!(balance, coin(3, ATOM_DENOM));
+
+    contract.close(&mut app, owner()).unwrap();
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(3, ATOM_DENOM));
+}
+
+#[test]
+fn percentage_commission_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![coin(10_000, ATOM_DENOM)])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    // 5% of the bid, at least 10 and at most 200
+    let model = CommissionModel::Percentage {
+        bps: 500,
+        min: Some(10u128.into()),
+        max: Some(200u128.into()),
+    };
+    let msg = AuctionMsg {
+        commission: model.clone(),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            0,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let resp = contract.query_commission(&app).unwrap();
+    assert_eq!(resp.model, model);
+    assert_eq!(resp.fee_collector, Some(owner()));
+
+    let resp = contract.query_preview_commission(&app, 100).unwrap();
+    assert_eq!(resp.commission, coin(10, ATOM_DENOM));
+    assert_eq!(resp.net, coin(90, ATOM_DENOM));
+
+    let resp = contract.query_preview_commission(&app, 1_000).unwrap();
+    assert_eq!(resp.commission, coin(50, ATOM_DENOM));
+
+    let resp = contract.query_preview_commission(&app, 10_000).unwrap();
+    assert_eq!(resp.commission, coin(200, ATOM_DENOM));
+
+    contract
+        .bid(&mut app, alice(), &coins(1_000, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid(&mut app, alice(), &coins(5_000, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_total_bid(&app, alice().to_string()).unwrap();
+    assert_eq!(resp.total, coin(5_750, ATOM_DENOM));
+    assert_eq!(resp.commission, coin(250, ATOM_DENOM));
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(250, ATOM_DENOM));
+}
+
+#[test]
+fn tiered_commission_should_works() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let tiered = |tiers: Vec<(u128, u16)>| AuctionMsg {
+        commission: CommissionModel::Tiered {
+            tiers: tiers
+                .into_iter()
+                .map(|(from, bps)| CommissionTier {
+                    from: from.into(),
+                    bps,
+                })
+                .collect(),
+        },
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            0,
+        )
+    };
+
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        code_id,
+        owner(),
+        &tiered(vec![(1_000, 300), (0, 500)]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidCommissionErr {
+            reason: "tiers must be sorted by from".into()
+        }
+    );
+
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        code_id,
+        owner(),
+        &tiered(vec![(0, 10_001)]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidCommissionErr {
+            reason: "bps must not exceed 10000".into()
+        }
+    );
+
+    // 5% below 1000, 3% from 1000 and 1% from 10000
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        code_id,
+        owner(),
+        &tiered(vec![(0, 500), (1_000, 300), (10_000, 100)]),
+    )
+    .unwrap();
+
+    let resp = contract.query_preview_commission(&app, 999).unwrap();
+    assert_eq!(resp.commission, coin(49, ATOM_DENOM));
+
+    let resp = contract.query_preview_commission(&app, 1_000).unwrap();
+    assert_eq!(resp.commission, coin(30, ATOM_DENOM));
+
+    let resp = contract.query_preview_commission(&app, 20_000).unwrap();
+    assert_eq!(resp.commission, coin(200, ATOM_DENOM));
+    assert_eq!(resp.net, coin(19_800, ATOM_DENOM));
+}
+
+#[test]
+fn close_pays_beneficiary_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    // a deployer creates the auction on behalf of the owner
+    let msg = AuctionMsg {
+        owner: Some(owner().to_string()),
+        beneficiary: Some(bob().to_string()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract =
+        BiddingContract::instantiate_with_msg(&mut app, code_id, parent(), &msg).unwrap();
+
+    let resp = contract.query_auctions(&app, None, None).unwrap();
+    assert_eq!(resp.auctions[0].owner, Some(owner()));
+    assert_eq!(resp.auctions[0].beneficiary, Some(bob()));
+
+    contract
+        .bid(&mut app, alice(), &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    // the deployer doesn't control close
+    let err = contract.close(&mut app, parent()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract.close(&mut app, owner()).unwrap();
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(4, ATOM_DENOM));
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(1, ATOM_DENOM));
+
+    let parent_balance = app.wrap().query_balance(parent(), ATOM_DENOM).unwrap();
+    assert_eq!(parent_balance, coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn instantiate_invalid_beneficiary_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        beneficiary: Some("Invalid Address".into()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+}
+
+#[test]
+fn transfer_ownership_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+    let contract =
+        BiddingContract::instantiate(&mut app, code_id, owner(), "bidding", 1, 1).unwrap();
+
+    let err = contract
+        .propose_owner(&mut app, alice(), &bob(), None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = contract.accept_ownership(&mut app, bob()).unwrap_err();
+    assert_eq!(err, ContractError::NoOwnershipProposalErr {});
+
+    // an expired proposal can't be accepted
+    let expiry = Expiration::AtHeight(app.block_info().height + 5);
+    contract
+        .propose_owner(&mut app, owner(), &bob(), Some(expiry))
+        .unwrap();
+
+    let resp = contract.query_ownership(&app).unwrap();
+    assert_eq!(resp.owner, Some(owner()));
+    assert_eq!(resp.pending_owner, Some(bob()));
+    assert_eq!(resp.pending_expiry, Some(expiry));
+
+    app.update_block(|block| block.height += 5);
+
+    let err = contract.accept_ownership(&mut app, bob()).unwrap_err();
+    assert_eq!(err, ContractError::OwnershipProposalExpiredErr {});
+
+    // a cancelled proposal can't be accepted
+    contract
+        .propose_owner(&mut app, owner(), &bob(), None)
+        .unwrap();
+    contract
+        .cancel_ownership_proposal(&mut app, owner())
+        .unwrap();
+
+    let err = contract.accept_ownership(&mut app, bob()).unwrap_err();
+    assert_eq!(err, ContractError::NoOwnershipProposalErr {});
+
+    contract
+        .propose_owner(&mut app, owner(), &bob(), None)
+        .unwrap();
+
+    let err = contract.accept_ownership(&mut app, alice()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract.accept_ownership(&mut app, bob()).unwrap();
+
+    let resp = contract.query_ownership(&app).unwrap();
+    assert_eq!(resp.owner, Some(bob()));
+    assert_eq!(resp.pending_owner, None);
+
+    // the new owner can't bid and controls close, the old one doesn't
+    let err = contract
+        .bid(&mut app, bob(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+
+    let err = contract.close(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract.close(&mut app, bob()).unwrap();
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(13, ATOM_DENOM));
+}
+
+#[test]
+fn renounce_ownership_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let contract =
+        BiddingContract::instantiate(&mut app, code_id, owner(), "bidding", 1, 1).unwrap();
+
+    // nobody could close the bid without an end
+    let err = contract.renounce_ownership(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::RenounceWithoutEndErr {});
+
+    let end = app.block_info().height + 10;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtHeight(end)),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = contract.open(&mut app, owner(), &msg).unwrap();
+
+    contract.renounce_ownership(&mut app, owner()).unwrap();
+
+    let resp = contract.query_ownership(&app).unwrap();
+    assert_eq!(resp.owner, None);
+
+    let err = contract
+        .propose_owner(&mut app, owner(), &bob(), None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the former owner keeps receiving the commission and the proceeds
+    contract
+        .bid(&mut app, alice(), &coins(4, ATOM_DENOM))
+        .unwrap();
+
+    app.update_block(|block| block.height = end);
+
+    contract.close(&mut app, bob()).unwrap();
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(4, ATOM_DENOM));
+}
+
+#[test]
+fn renounce_ownership_while_paused_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let end = app.block_info().height + 10;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtHeight(end)),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract.pause(&mut app, owner()).unwrap();
+    contract.renounce_ownership(&mut app, owner()).unwrap();
+
+    // renouncing lifts the pause, nobody could unpause later
+    assert!(!contract.query_status(&app).unwrap().paused);
+    contract
+        .bid(&mut app, alice(), &coins(4, ATOM_DENOM))
+        .unwrap();
+}
+
+#[test]
+fn migrate_legacy_auction_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let legacy_code_id = legacy::store_code(&mut app);
+    let addr = legacy::instantiate_legacy(&mut app, legacy_code_id, "bidding", "0.1.0");
+
+    // bid on the legacy auction, the commission is kept in the contract
+    let bidding = legacy::ExecuteMsg::Bidding {};
+    app.execute_contract(alice(), addr.clone(), &bidding, &coins(3, ATOM_DENOM))
+        .unwrap();
+    app.execute_contract(bob(), addr.clone(), &bidding, &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    // only the admin migrates
+    app.migrate_contract(alice(), addr.clone(), &MigrateMsg {}, code_id)
+        .unwrap_err();
+
+    let contract = BiddingContract::migrate(&mut app, owner(), &addr, code_id).unwrap();
+
+    let version = cw2::query_contract_info(&app.wrap(), &addr).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    // the commission of each bidder is paid out on migration
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(2, ATOM_DENOM));
+
+    let resp = contract.query_total_bid(&app, alice().to_string()).unwrap();
+    assert_eq!(resp.total, coin(2, ATOM_DENOM));
+    assert_eq!(resp.commission, coin(1, ATOM_DENOM));
+
+    let highest = contract.query_highest_of_bid(&app).unwrap();
+    assert_eq!(
+        highest.bid,
+        Some(Bid {
+            bid: coin(4, ATOM_DENOM),
+            bidder: bob()
+        })
+    );
+
+    // the bidding goes on in the migrated auction
+    let err = contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BidTooLowErr {
+            total: coin(4, ATOM_DENOM),
+            minimum: coin(5, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .bid(&mut app, alice(), &coins(4, ATOM_DENOM))
+        .unwrap();
+
+    contract.close(&mut app, owner()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(8, ATOM_DENOM));
+
+    contract.retract(&mut app, bob(), None).unwrap();
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(9, ATOM_DENOM));
+
+    let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
+    assert_eq!(balance, coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn migrate_closed_legacy_auction_should_fails() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let legacy_code_id = legacy::store_code(&mut app);
+    let addr = legacy::instantiate_legacy(&mut app, legacy_code_id, "bidding", "0.1.0");
+
+    app.execute_contract(
+        alice(),
+        addr.clone(),
+        &legacy::ExecuteMsg::Bidding {},
+        &coins(3, ATOM_DENOM),
+    )
+    .unwrap();
+    app.execute_contract(owner(), addr.clone(), &legacy::ExecuteMsg::Close {}, &[])
+        .unwrap();
+
+    let code_id = BiddingContract::store_code(&mut app);
+    let err = BiddingContract::migrate(&mut app, owner(), &addr, code_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMigrationErr {
+            reason: "cannot migrate a closed auction".into()
+        }
+    );
+}
+
+#[test]
+fn migrate_invalid_version_should_fails() {
+    let mut app = App::default();
+
+    let legacy_code_id = legacy::store_code(&mut app);
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let addr = legacy::instantiate_legacy(&mut app, legacy_code_id, "other", "0.1.0");
+    let err = BiddingContract::migrate(&mut app, owner(), &addr, code_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMigrationErr {
+            reason: "cannot migrate from other".into()
+        }
+    );
+
+    let addr = legacy::instantiate_legacy(&mut app, legacy_code_id, "bidding", "9.0.0");
+    let err = BiddingContract::migrate(&mut app, owner(), &addr, code_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMigrationErr {
+            reason: format!(
+                "cannot downgrade from 9.0.0 to {}",
+                env!("CARGO_PKG_VERSION")
+            )
+        }
+    );
+
+    // migrating to the same version keeps the auctions
+    let msg = InstantiateMsg {
+        creators: None,
+        auction: Some(Box::new(AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        ))),
+    };
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            owner(),
+            &msg,
+            &[],
+            "bidding",
+            Some(owner().to_string()),
+        )
+        .unwrap();
+
+    let contract = BiddingContract::migrate(&mut app, owner(), &addr, code_id).unwrap();
+    assert_eq!(
+        contract.query_status(&app).unwrap().status,
+        BidStatus::Opening {}
+    );
+}
+
+#[test]
+fn paused_bid_should_fails() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let end = app.block_info().height + 10;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtHeight(end)),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let err = contract.pause(&mut app, alice()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract.pause(&mut app, owner()).unwrap();
+    assert!(contract.query_status(&app).unwrap().paused);
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidPausedErr {});
+
+    contract.unpause(&mut app, owner()).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    // a pause only stops the bids, anyone still closes after the end
+    contract.pause(&mut app, owner()).unwrap();
+    app.update_block(|block| block.height = end);
+    contract.close(&mut app, bob()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+
+    let err = contract.pause(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyClosed {});
+}
+
+#[test]
+fn emergency_cancel_refunds_every_bidder_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let nft = instantiate_cw721(&mut app, &[("punk", owner())]);
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        nft: Some(nft.to_string()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .deposit_nft(&mut app, owner(), &nft, "punk")
+        .unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid(&mut app, bob(), &coins(4, ATOM_DENOM))
+        .unwrap();
+
+    let err = contract.emergency_cancel(&mut app, alice()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    contract.pause(&mut app, owner()).unwrap();
+    contract.emergency_cancel(&mut app, owner()).unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, BidStatus::Closed {});
+    assert!(resp.emergency);
+    assert_eq!(contract.query_winner(&app).unwrap().winner, None);
+    assert_eq!(query_nft_owner(&app, &nft, "punk"), owner());
+
+    let err = contract.close(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyClosed {});
+
+    // the highest bidder retracts as well
+    contract.retract(&mut app, bob(), None).unwrap();
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(9, ATOM_DENOM));
+
+    let alice_balance = app.wrap().query_balance(alice(), ATOM_DENOM).unwrap();
+    assert_eq!(alice_balance, coin(9, ATOM_DENOM));
+
+    let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
+    assert_eq!(balance, coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn cancel_refunds_full_deposit_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+    let contract =
+        BiddingContract::instantiate(&mut app, code_id, owner(), "bidding", 1, 1).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid(&mut app, bob(), &coins(4, ATOM_DENOM))
+        .unwrap();
+
+    let err = contract.cancel(&mut app, alice(), &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the owner returns the commission it collected
+    let err = contract.cancel(&mut app, owner(), &[]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CommissionRefundErr {
+            required: coins(2, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .cancel(&mut app, owner(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, BidStatus::Cancelled {});
+    assert_eq!(contract.query_winner(&app).unwrap().winner, None);
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(5, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidCancelledErr {});
+
+    let err = contract.close(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::BidCancelledErr {});
+
+    contract.retract(&mut app, bob(), None).unwrap();
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    assert_eq!(
+        app.wrap().query_balance(alice(), ATOM_DENOM).unwrap(),
+        ten_atom()
+    );
+    assert_eq!(
+        app.wrap().query_balance(bob(), ATOM_DENOM).unwrap(),
+        ten_atom()
+    );
+    assert_eq!(
+        app.wrap().query_balance(owner(), ATOM_DENOM).unwrap(),
+        zero_atom()
+    );
+}
+
+#[test]
+fn cancel_cw20_auction_should_works() {
+    let mut app = App::default();
+
+    let cw20 = instantiate_cw20(&mut app, &[(alice(), 10)]);
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg::new(
+        "bidding".into(),
+        UncheckedDenom::Cw20(cw20.to_string()),
+        1,
+        1,
+    );
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract.bid_cw20(&mut app, alice(), &cw20, 4).unwrap();
+    assert_eq!(query_cw20_balance(&app, &cw20, &owner()), 1);
+
+    contract.cancel_cw20(&mut app, owner(), &cw20, 1).unwrap();
+    assert_eq!(
+        contract.query_status(&app).unwrap().status,
+        BidStatus::Cancelled {}
+    );
+
+    contract.retract(&mut app, alice(), None).unwrap();
+    assert_eq!(query_cw20_balance(&app, &cw20, &alice()), 10);
+    assert_eq!(query_cw20_balance(&app, &cw20, &contract.addr()), 0);
+}
+
+#[test]
+fn close_below_reserve_without_winner_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        reserve: Some(Reserve {
+            price: 6u128.into(),
+            hidden: true,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid(&mut app, bob(), &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    // the hidden price is not revealed
+    let resp = contract.query_reserve(&app).unwrap();
+    assert!(resp.has_reserve);
+    assert!(!resp.met);
+    assert_eq!(resp.price, None);
+
+    contract.close(&mut app, owner()).unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, BidStatus::Closed {});
+    assert_eq!(contract.query_winner(&app).unwrap().winner, None);
+
+    // the owner keeps only the commission
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(2, ATOM_DENOM));
+
+    contract.retract(&mut app, bob(), None).unwrap();
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    let bob_balance = app.wrap().query_balance(bob(), ATOM_DENOM).unwrap();
+    assert_eq!(bob_balance, coin(9, ATOM_DENOM));
+
+    let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
+    assert_eq!(balance, coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn close_reserve_met_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        reserve: Some(Reserve {
+            price: 4u128.into(),
+            hidden: false,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let resp = contract.query_reserve(&app).unwrap();
+    assert!(!resp.met);
+    assert_eq!(resp.price, Some(4u128.into()));
+
+    contract
+        .bid(&mut app, alice(), &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_reserve(&app).unwrap();
+    assert!(resp.met);
+
+    contract.close(&mut app, owner()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(5, ATOM_DENOM));
+}
+
+#[test]
+fn close_with_revealed_reserve_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let salt = b"secret salt";
+    let msg = AuctionMsg {
+        reserve_commitment: Some(Reserve::commitment(4u128.into(), salt)),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    // unknown until revealed
+    let resp = contract.query_reserve(&app).unwrap();
+    assert!(resp.has_reserve);
+    assert!(!resp.met);
+    assert_eq!(resp.price, None);
+
+    let err = contract.close(&mut app, owner()).unwrap_err();
+    assert_eq!(err, ContractError::InvalidRevealErr {});
+
+    let reveal = Reveal {
+        reserve: 3u128.into(),
+        salt: salt.into(),
+    };
+    let err = contract
+        .close_with_reveal(&mut app, owner(), Some(reveal))
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidRevealErr {});
+
+    let reveal = Reveal {
+        reserve: 4u128.into(),
+        salt: salt.into(),
+    };
+    contract
+        .close_with_reveal(&mut app, owner(), Some(reveal))
+        .unwrap();
+
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+
+    // the revealed reserve is kept for audit
+    let resp = contract.query_reserve(&app).unwrap();
+    assert!(resp.met);
+    assert_eq!(resp.price, Some(4u128.into()));
+    assert_eq!(
+        resp.commitment,
+        Some(Reserve::commitment(4u128.into(), salt))
+    );
+}
+
+#[test]
+fn close_unrevealed_reserve_after_end_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let end = app.block_info().height + 10;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtHeight(end)),
+        reserve_commitment: Some(Reserve::commitment(4u128.into(), b"secret salt")),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    // the owner has the reveal period to reveal
+    app.update_block(|block| block.height = end);
+    let err = contract.close(&mut app, bob()).unwrap_err();
+    assert_eq!(err, ContractError::RevealOpeningErr {});
+
+    // the owner never reveals, anyone closes and the highest bid wins
+    app.update_block(|block| block.height = end + RESERVE_REVEAL_BLOCKS);
+    contract.close(&mut app, bob()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+    assert!(contract.query_reserve(&app).unwrap().met);
+
+    let balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(balance, coin(5, ATOM_DENOM));
+}
+
+#[test]
+fn instantiate_invalid_reserve_commitment_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        reserve_commitment: Some(vec![1u8; 20].into()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidReserveErr {
+            reason: "the commitment must be a sha256 hash".into()
+        }
+    );
+
+    let msg = AuctionMsg {
+        reserve: Some(Reserve {
+            price: 4u128.into(),
+            hidden: true,
+        }),
+        reserve_commitment: Some(Reserve::commitment(4u128.into(), b"salt")),
+        ..msg
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidReserveErr {
+            reason: "set either a reserve or a commitment".into()
+        }
+    );
+}
+
+#[test]
+fn instantiate_invalid_buy_now_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        buy_now: Some(BuyNow {
+            price: 5u128.into(),
+            expire_ratio: None,
+        }),
+        reserve_commitment: Some(Reserve::commitment(8u128.into(), b"salt")),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBuyNowErr {
+            reason: "buy now can't be set with a reserve commitment".into()
+        }
+    );
+
+    let msg = AuctionMsg {
+        reserve_commitment: None,
+        reserve: Some(Reserve {
+            price: 8u128.into(),
+            hidden: true,
+        }),
+        ..msg
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBuyNowErr {
+            reason: "the price must not be below the reserve".into()
+        }
+    );
+}
+
+#[test]
+fn buy_now_closes_auction_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        buy_now: Some(BuyNow {
+            price: 5u128.into(),
+            expire_ratio: None,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid(&mut app, bob(), &coins(6, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, BidStatus::Closed {});
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(bob()));
+
+    // paid in the same transaction, along with the commission of both bids
+    let owner_balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(owner_balance, coin(7, ATOM_DENOM));
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyClosed {});
+
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    let balance = contract.query_balance(&app, ATOM_DENOM).unwrap();
+    assert_eq!(balance, coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn buy_now_overshoot_retracts_rest_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(100, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        buy_now: Some(BuyNow {
+            price: 5u128.into(),
+            expire_ratio: None,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(51, ATOM_DENOM))
+        .unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+    assert_eq!(
+        contract.query_clearing_price(&app).unwrap().price,
+        Some(coin(5, ATOM_DENOM))
+    );
+
+    // only the buy now price and the commission are paid, the winner retracts the rest
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(owner()), coin(6, ATOM_DENOM));
+    assert_eq!(balance(alice()), coin(94, ATOM_DENOM));
+    assert_eq!(balance(contract.addr()), coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn buy_now_expires_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(20, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let buy_now = BuyNow {
+        price: 10u128.into(),
+        expire_ratio: Some(Decimal::percent(50)),
+    };
+    let msg = AuctionMsg {
+        buy_now: Some(buy_now.clone()),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(5, ATOM_DENOM))
+        .unwrap();
+    assert_eq!(contract.query_buy_now(&app).unwrap().buy_now, Some(buy_now));
+
+    // the highest bid exceeds half of the buy now price
+    contract
+        .bid(&mut app, bob(), &coins(7, ATOM_DENOM))
+        .unwrap();
+    assert_eq!(contract.query_buy_now(&app).unwrap().buy_now, None);
+
+    // reaching the former price no longer closes the bid
+    contract
+        .bid(&mut app, alice(), &coins(7, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, BidStatus::Opening {});
+    assert_eq!(contract.query_winner(&app).unwrap().winner, None);
+}
+
+#[test]
+fn increment_bps_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(30, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), coins(30, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        increment_bps: Some(1_000),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(21, ATOM_DENOM))
+        .unwrap();
+
+    // 10% over the highest 20, plus the commission
+    let resp = contract.query_minimum_next_bid(&app, bob()).unwrap();
+    assert_eq!(resp.amount, coin(23, ATOM_DENOM));
+
+    // the highest bidder only tops up the increment
+    let resp = contract.query_minimum_next_bid(&app, alice()).unwrap();
+    assert_eq!(resp.amount, coin(3, ATOM_DENOM));
+
+    let err = contract
+        .bid(&mut app, bob(), &coins(22, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BidTooLowErr {
+            total: coin(21, ATOM_DENOM),
+            minimum: coin(22, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .bid(&mut app, bob(), &coins(23, ATOM_DENOM))
+        .unwrap();
+
+    let highest = contract.query_highest_of_bid(&app).unwrap();
+    assert_eq!(
+        highest.bid,
+        Some(Bid {
+            bid: coin(22, ATOM_DENOM),
+            bidder: bob()
+        })
+    );
+}
+
+#[test]
+fn minimum_next_bid_with_percentage_commission_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(100, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), coins(100, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        commission: CommissionModel::Percentage {
+            bps: 1_000,
+            min: None,
+            max: None,
+        },
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            5,
+            0,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    // the first bid only needs the tick
+    let resp = contract.query_minimum_next_bid(&app, alice()).unwrap();
+    assert_eq!(resp.amount, coin(5, ATOM_DENOM));
+
+    contract
+        .bid(&mut app, alice(), &coins(50, ATOM_DENOM))
+        .unwrap();
+
+    // 45 kept, so 50 must be kept out of the next bid
+    let resp = contract.query_minimum_next_bid(&app, bob()).unwrap();
+    assert_eq!(resp.amount, coin(55, ATOM_DENOM));
+
+    let err = contract
+        .bid(&mut app, bob(), &coins(54, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BidTooLowErr {
+            total: coin(49, ATOM_DENOM),
+            minimum: coin(50, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .bid(&mut app, bob(), &coins(55, ATOM_DENOM))
+        .unwrap();
+}
+
+#[test]
+fn minimum_next_bid_with_discount_tier_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(20_000, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), coins(20_000, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    // bids from 10000 on pay a lower commission
+    let msg = AuctionMsg {
+        commission: CommissionModel::Tiered {
+            tiers: vec![
+                CommissionTier {
+                    from: Uint128::zero(),
+                    bps: 500,
+                },
+                CommissionTier {
+                    from: 10_000u128.into(),
+                    bps: 200,
+                },
+            ],
+        },
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            100,
+            0,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, bob(), &coins(9_999, ATOM_DENOM))
+        .unwrap();
+
+    // 9600 must be kept, which the start of the discount bracket already does
+    let resp = contract.query_minimum_next_bid(&app, alice()).unwrap();
+    assert_eq!(resp.amount, coin(10_000, ATOM_DENOM));
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(9_999, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BidTooLowErr {
+            total: coin(9_500, ATOM_DENOM),
+            minimum: coin(9_600, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .bid(&mut app, alice(), &coins(10_000, ATOM_DENOM))
+        .unwrap();
+}
+
+#[test]
+fn sealed_bid_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        for bidder in [alice(), bob(), parent()] {
+            router
+                .bank
+                .init_balance(storage, &bidder, vec![ten_atom()])
+                .unwrap();
+        }
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let now = app.block_info().time;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtTime(now.plus_seconds(100))),
+        kind: Some(AuctionKind::SealedBid {
+            reveal_end: Expiration::AtTime(now.plus_seconds(200)),
+            forfeit_bps: 5_000,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::WrongAuctionKindErr {});
+
+    // the deposits hide the bids, 9 is kept of each after the commission
+    let bids = [
+        (alice(), 6, b"alice"),
+        (bob(), 8, b"bob.."),
+        (parent(), 7, b"paren"),
+    ];
+    for (bidder, amount, salt) in &bids {
+        contract
+            .commit(
+                &mut app,
+                bidder.clone(),
+                commitment(Uint128::new(*amount), *salt),
+                &[ten_atom()],
+            )
+            .unwrap();
+    }
+
+    let err = contract.reveal(&mut app, alice(), 6, b"alice").unwrap_err();
+    assert_eq!(err, ContractError::BidIsOpening {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    // pausing can't keep the bidders from revealing
+    contract.pause(&mut app, owner()).unwrap();
+
+    let err = contract.reveal(&mut app, alice(), 6, b"bob..").unwrap_err();
+    assert_eq!(err, ContractError::InvalidBidRevealErr {});
+
+    contract.reveal(&mut app, alice(), 6, b"alice").unwrap();
+    contract.reveal(&mut app, bob(), 8, b"bob..").unwrap();
+
+    let resp = contract.query_commitment(&app, bob()).unwrap();
+    assert!(resp.commitment.unwrap().revealed);
+
+    let err = contract.close(&mut app, alice()).unwrap_err();
+    assert_eq!(err, ContractError::RevealOpeningErr {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract
+        .reveal(&mut app, parent(), 7, b"paren")
+        .unwrap_err();
+    assert_eq!(err, ContractError::RevealEndedErr {});
+
+    contract.close(&mut app, alice()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(bob()));
+
+    // the winner pays the revealed amount and retracts the rest
+    contract.retract(&mut app, bob(), None).unwrap();
+    contract.retract(&mut app, alice(), None).unwrap();
+    contract.retract(&mut app, parent(), None).unwrap();
+
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(bob()), coin(1, ATOM_DENOM));
+    assert_eq!(balance(alice()), coin(9, ATOM_DENOM));
+    // half of the unrevealed deposit is forfeited
+    assert_eq!(balance(parent()), coin(5, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(15, ATOM_DENOM));
+    assert_eq!(balance(contract.addr()), coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn instantiate_invalid_sealed_bid_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let now = app.block_info().time;
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::SealedBid {
+            reveal_end: Expiration::AtTime(now.plus_seconds(200)),
+            forfeit_bps: 0,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "reveal_end must be after end".into()
+        }
+    );
+
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtTime(now.plus_seconds(100))),
+        weights: Some(vec![DenomWeight {
+            denom: "uinj".into(),
+            weight: Decimal::one(),
+        }]),
+        ..msg
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "the kind accepts the bidding denom only".into()
+        }
+    );
+}
+
+#[test]
+fn second_price_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::SecondPrice {}),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(5, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid(&mut app, bob(), &coins(8, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_highest_of_bid(&app).unwrap();
+    assert_eq!(
+        resp.runner_up,
+        Some(Bid {
+            bid: coin(4, ATOM_DENOM),
+            bidder: alice()
+        })
+    );
+    assert_eq!(contract.query_clearing_price(&app).unwrap().price, None);
+
+    contract.close(&mut app, owner()).unwrap();
+
+    // bob wins with 7 and pays the runner-up 4 plus the tick
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(bob()));
+    let resp = contract.query_clearing_price(&app).unwrap();
+    assert_eq!(resp.price, Some(coin(5, ATOM_DENOM)));
+
+    contract.retract(&mut app, bob(), None).unwrap();
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(bob()), coin(4, ATOM_DENOM));
+    assert_eq!(balance(alice()), coin(9, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(7, ATOM_DENOM));
+    assert_eq!(balance(contract.addr()), coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn second_price_without_runner_up_pays_reserve_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::SecondPrice {}),
+        reserve: Some(Reserve {
+            price: 3u128.into(),
+            hidden: false,
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(10, ATOM_DENOM))
+        .unwrap();
+    contract.close(&mut app, owner()).unwrap();
+
+    let resp = contract.query_clearing_price(&app).unwrap();
+    assert_eq!(resp.price, Some(coin(3, ATOM_DENOM)));
+
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(alice()), coin(6, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(4, ATOM_DENOM));
+}
+
+#[test]
+fn dutch_auction_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(100, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), coins(100, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Dutch {
+            start_price: 100u128.into(),
+            floor_price: 40u128.into(),
+            decay: Decay::Linear {
+                amount: 2u128.into(),
+                per: Duration::Time(1),
+            },
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let resp = contract.query_current_price(&app).unwrap();
+    assert_eq!(resp.price, coin(100, ATOM_DENOM));
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(100, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::WrongAuctionKindErr {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+
+    let resp = contract.query_current_price(&app).unwrap();
+    assert_eq!(resp.price, coin(80, ATOM_DENOM));
+
+    let err = contract
+        .buy(&mut app, alice(), &coins(75, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BidTooLowErr {
+            total: coin(75, ATOM_DENOM),
+            minimum: coin(80, ATOM_DENOM)
+        }
+    );
+
+    // the change is refunded right away
+    contract
+        .buy(&mut app, alice(), &coins(90, ATOM_DENOM))
+        .unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, BidStatus::Closed {});
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+    let resp = contract.query_clearing_price(&app).unwrap();
+    assert_eq!(resp.price, Some(coin(80, ATOM_DENOM)));
+
+    let err = contract
+        .buy(&mut app, bob(), &coins(90, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyClosed {});
+
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(alice()), coin(20, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(80, ATOM_DENOM));
+    assert_eq!(balance(contract.addr()), coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn dutch_auction_step_decay_should_works() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let kind = AuctionKind::Dutch {
+        start_price: 100u128.into(),
+        floor_price: 40u128.into(),
+        decay: Decay::Step {
+            amount: 10u128.into(),
+            per: Duration::Height(5),
+        },
+    };
+    let msg = AuctionMsg {
+        kind: Some(kind),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    app.update_block(|block| block.height += 7);
+
+    let resp = contract.query_current_price(&app).unwrap();
+    assert_eq!(resp.price, coin(90, ATOM_DENOM));
+
+    // never below the floor
+    app.update_block(|block| block.height += 100);
+
+    let resp = contract.query_current_price(&app).unwrap();
+    assert_eq!(resp.price, coin(40, ATOM_DENOM));
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Dutch {
+            start_price: 40u128.into(),
+            floor_price: 40u128.into(),
+            decay: Decay::Step {
+                amount: 10u128.into(),
+                per: Duration::Height(5),
+            },
+        }),
+        ..msg
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "start_price must be above floor_price".into()
+        }
+    );
+
+    // the price decays on its own, there is nothing to outbid
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Dutch {
+            start_price: 50u128.into(),
+            floor_price: 40u128.into(),
+            decay: Decay::Step {
+                amount: 10u128.into(),
+                per: Duration::Height(5),
+            },
+        }),
+        increment_bps: Some(100),
+        ..msg
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "a dutch auction has no buy now, reserve, extension or increment".into()
+        }
+    );
+}
+
+#[test]
+fn reverse_auction_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner(), coins(100, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Reverse {
+            budget: 100u128.into(),
+            bond: 5u128.into(),
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            2,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_funds(
+        &mut app,
+        code_id,
+        owner(),
+        &msg,
+        &coins(100, ATOM_DENOM),
+    )
+    .unwrap();
+
+    let err = contract
+        .ask(&mut app, alice(), 120, &coins(5, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AskTooHighErr {
+            price: coin(120, ATOM_DENOM),
+            maximum: coin(100, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .ask(&mut app, alice(), 90, &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    // undercut by at least the tick
+    let err = contract
+        .ask(&mut app, bob(), 89, &coins(5, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AskTooHighErr {
+            price: coin(89, ATOM_DENOM),
+            maximum: coin(88, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .ask(&mut app, bob(), 80, &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    // the bond is posted once
+    let err = contract
+        .ask(&mut app, alice(), 78, &coins(5, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBondErr {
+            bond: coin(0, ATOM_DENOM)
+        }
+    );
+    contract.ask(&mut app, alice(), 78, &[]).unwrap();
+
+    contract.close(&mut app, owner()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+
+    contract.retract(&mut app, alice(), None).unwrap();
+    contract.retract(&mut app, bob(), None).unwrap();
+
+    // alice is paid her ask less the commission, the owner gets the rest of the budget
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(alice()), coin(87, ATOM_DENOM));
+    assert_eq!(balance(bob()), coin(10, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(23, ATOM_DENOM));
+    assert_eq!(balance(contract.addr()), coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn open_with_funds_should_fails() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner(), coins(10, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    // only a reverse auction escrows funds
+    let msg = AuctionMsg::new(
+        "bidding".into(),
+        UncheckedDenom::Native(ATOM_DENOM.into()),
+        1,
+        1,
+    );
+    let err = BiddingContract::instantiate_with_funds(
+        &mut app,
+        code_id,
+        owner(),
+        &msg,
+        &coins(7, ATOM_DENOM),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let err = app
+        .execute_contract(
+            owner(),
+            contract.addr(),
+            &ExecuteMsg::Open(Box::new(msg)),
+            &coins(7, ATOM_DENOM),
+        )
+        .unwrap_err()
+        .downcast::<ContractError>()
+        .unwrap();
+    assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+
+    let balance = app.wrap().query_balance(owner(), ATOM_DENOM).unwrap();
+    assert_eq!(balance, coin(10, ATOM_DENOM));
+}
+
+#[test]
+fn reverse_auction_refunds_budget_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner(), coins(100, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Reverse {
+            budget: 100u128.into(),
+            bond: 5u128.into(),
+        }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+
+    // asks undercut by the tick only
+    let invalid = AuctionMsg {
+        increment_bps: Some(100),
+        ..msg.clone()
+    };
+    let err = BiddingContract::instantiate_with_funds(
+        &mut app,
+        code_id,
+        owner(),
+        &invalid,
+        &coins(100, ATOM_DENOM),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "a reverse auction has no buy now, reserve, nft or increment".into()
+        }
+    );
+
+    let err = BiddingContract::instantiate_with_funds(
+        &mut app,
+        code_id,
+        owner(),
+        &msg,
+        &coins(50, ATOM_DENOM),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BudgetNotEscrowedErr {
+            budget: coin(100, ATOM_DENOM)
+        }
+    );
+
+    let contract = BiddingContract::instantiate_with_funds(
+        &mut app,
+        code_id,
+        owner(),
+        &msg,
+        &coins(100, ATOM_DENOM),
+    )
+    .unwrap();
+
+    let err = contract
+        .bid(&mut app, bob(), &coins(5, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::WrongAuctionKindErr {});
+
+    let err = contract
+        .ask(&mut app, bob(), 0, &coins(5, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidAskErr {});
+
+    contract
+        .ask(&mut app, bob(), 1, &coins(5, ATOM_DENOM))
+        .unwrap();
+
+    // nothing undercuts the lowest ask by a full tick anymore
+    let err = contract.ask(&mut app, bob(), 1, &[]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AskTooHighErr {
+            price: coin(1, ATOM_DENOM),
+            maximum: coin(0, ATOM_DENOM)
+        }
+    );
+
+    contract.emergency_cancel(&mut app, owner()).unwrap();
+    contract.retract(&mut app, bob(), None).unwrap();
+
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(owner()), coin(100, ATOM_DENOM));
+    assert_eq!(balance(bob()), coin(10, ATOM_DENOM));
+}
+
+#[test]
+fn multi_unit_auction_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(100, ATOM_DENOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), coins(100, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::MultiUnit { quantity: 5 }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid_units(&mut app, alice(), 10, 3, &coins(31, ATOM_DENOM))
+        .unwrap();
+    contract
+        .bid_units(&mut app, bob(), 8, 4, &coins(33, ATOM_DENOM))
+        .unwrap();
+
+    // bob is partially filled and sets the provisional clearing price
+    let resp = contract.query_unit_allocation(&app, bob()).unwrap();
+    assert_eq!(resp.units, 2);
+    assert_eq!(resp.clearing_price, Some(coin(8, ATOM_DENOM)));
+
+    contract.close(&mut app, owner()).unwrap();
+
+    let resp = contract.query_unit_allocation(&app, alice()).unwrap();
+    assert_eq!(resp.units, 3);
+    assert_eq!(resp.bid.unwrap().allocated, 3);
+    assert_eq!(
+        contract.query_clearing_price(&app).unwrap().price,
+        Some(coin(8, ATOM_DENOM))
+    );
+
+    contract.retract(&mut app, alice(), None).unwrap();
+    contract.retract(&mut app, bob(), None).unwrap();
+
+    // every winner pays 8 per unit, the owner gets the proceeds and the commission
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(alice()), coin(75, ATOM_DENOM));
+    assert_eq!(balance(bob()), coin(83, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(42, ATOM_DENOM));
+    assert_eq!(balance(contract.addr()), coin(0, ATOM_DENOM));
+}
+
+#[test]
+fn invalid_unit_bid_should_fails() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), coins(100, ATOM_DENOM))
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::MultiUnit { quantity: 0 }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "the quantity must not be zero".into()
+        }
+    );
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::MultiUnit { quantity: 5 }),
+        increment_bps: Some(100),
+        ..msg
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "a multi-unit auction has no extension or increment".into()
+        }
+    );
+
+    let msg = AuctionMsg {
+        increment_bps: None,
+        ..msg
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    let err = contract
+        .bid(&mut app, alice(), &coins(10, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::WrongAuctionKindErr {});
+
+    let err = contract
+        .bid_units(&mut app, alice(), 2, 6, &coins(13, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidUnitsErr { max: 5 });
+
+    // the deposit after the commission must cover every unit
+    let err = contract
+        .bid_units(&mut app, alice(), 2, 5, &coins(10, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBidErr {
+            total_bid: coin(9, ATOM_DENOM)
+        }
+    );
+
+    contract
+        .bid_units(&mut app, alice(), 2, 5, &coins(11, ATOM_DENOM))
+        .unwrap();
+    let err = contract
+        .bid_units(&mut app, alice(), 3, 1, &coins(4, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidAlreadyCommittedErr {});
+}
+
+#[test]
+fn penny_auction_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Penny { countdown: 60 }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    // each bid raises the price by the tick only
+    let err = contract
+        .bid(&mut app, bob(), &coins(4, ATOM_DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PennyBidErr {
+            price: coin(2, ATOM_DENOM)
+        }
+    );
+    contract
+        .bid(&mut app, bob(), &coins(3, ATOM_DENOM))
+        .unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(30));
+    contract
+        .bid(&mut app, alice(), &coins(3, ATOM_DENOM))
+        .unwrap();
+
+    // the last bid restarted the countdown
+    app.update_block(|block| block.time = block.time.plus_seconds(59));
+    let err = contract.close(&mut app, bob()).unwrap_err();
+    assert_eq!(err, ContractError::BidIsOpening {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(1));
+    contract.close(&mut app, bob()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+
+    contract.retract(&mut app, bob(), None).unwrap();
+
+    // the owner keeps every fee and the final price of 3
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(alice()), coin(5, ATOM_DENOM));
+    assert_eq!(balance(bob()), coin(9, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(6, ATOM_DENOM));
+}
+
+#[test]
+fn penny_auction_cancel_keeps_fees_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Penny { countdown: 60 }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    // the fees are not refunded, so none is sent back on cancel
+    contract.cancel(&mut app, owner(), &[]).unwrap();
+    contract.retract(&mut app, alice(), None).unwrap();
+
+    let balance = |addr| app.wrap().query_balance(addr, ATOM_DENOM).unwrap();
+    assert_eq!(balance(alice()), coin(9, ATOM_DENOM));
+    assert_eq!(balance(owner()), coin(1, ATOM_DENOM));
+}
+
+#[test]
+fn penny_auction_keeps_end_should_works() {
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice(), vec![ten_atom()])
+            .unwrap();
+    });
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let now = app.block_info().time;
+    let msg = AuctionMsg {
+        end: Some(Expiration::AtTime(now.plus_seconds(100))),
+        kind: Some(AuctionKind::Penny { countdown: 60 }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let contract = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap();
+
+    contract
+        .bid(&mut app, alice(), &coins(2, ATOM_DENOM))
+        .unwrap();
+
+    // the countdown never ends the auction before the configured end
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    let err = contract.close(&mut app, alice()).unwrap_err();
+    assert_eq!(err, ContractError::BidIsOpening {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(40));
+    contract.close(&mut app, alice()).unwrap();
+    assert_eq!(contract.query_winner(&app).unwrap().winner, Some(alice()));
+}
+
+#[test]
+fn instantiate_invalid_penny_should_fails() {
+    let mut app = App::default();
+
+    let code_id = BiddingContract::store_code(&mut app);
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Penny { countdown: 0 }),
+        ..AuctionMsg::new(
+            "bidding".into(),
+            UncheckedDenom::Native(ATOM_DENOM.into()),
+            1,
+            1,
+        )
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "the countdown must not be zero".into()
+        }
+    );
+
+    let msg = AuctionMsg {
+        kind: Some(AuctionKind::Penny { countdown: 60 }),
+        end: Some(Expiration::AtHeight(app.block_info().height + 10)),
+        ..msg
+    };
+    let err = BiddingContract::instantiate_with_msg(&mut app, code_id, owner(), &msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAuctionKindErr {
+            reason: "the countdown requires the end to be a time".into()
+        }
+    );
+}
@@ -1,39 +1,651 @@
 use cosmwasm_schema::cw_serde;
 /// Define Bidding contract state and storage item
-use cosmwasm_std::{Addr, Coin};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{
+    Addr, BlockInfo, Coin, Decimal, HexBinary, Order, StdResult, Storage, Timestamp, Uint128,
+};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{Duration, Expiration, Scheduled};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::ContractError;
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct State {
-    // contract owner
-    pub owner: Addr,
+    // contract owner, None once renounced
+    pub owner: Option<Addr>,
+    // the proposed owner until it accepts the ownership
+    pub pending_owner: Option<Addr>,
+    // the proposal can't be accepted after this block height or time
+    pub pending_expiry: Option<Expiration>,
+    // receives the proceeds on close, the owner if missing
+    pub beneficiary: Option<Addr>,
     // bidding name
     pub name: String,
+    // the bidding denom, all bids are compared in this denom
+    pub denom: String,
+    // the cw20 contract when bidding in a cw20 token, the denom is its address
+    pub cw20: Option<Addr>,
+    // other accepted denoms and their weights converting into the bidding denom
+    pub weights: Vec<DenomWeight>,
     // bid tick
     pub tick: Coin,
-    // commission
-    pub commission: Coin,
-    // bid status, Opening or Closed, default is Opening
+    // commission taken from each bid, in the bidding denom
+    pub commission: CommissionModel,
+    // receives the commission of each bid, the owner if missing
+    pub fee_collector: Option<Addr>,
+    // bid status, NotStarted, Opening, Closed or Cancelled, default is Opening
     pub status: BidStatus,
-    // highest bid
+    // the block height or time from which bids are accepted
+    pub start: Option<Scheduled>,
+    // the block height or time after which bids are rejected and anyone can close
+    pub end: Option<Expiration>,
+    // anti-sniping extension of the end time on late bids
+    pub extension: Option<Extension>,
+    // the cw721 contract of the auctioned nft, bids are accepted once it is deposited
+    pub nft: Option<Addr>,
+    // the nft deposited by the owner, delivered to the winner on close
+    pub item: Option<AuctionItem>,
+    // highest bid, the lowest ask of a reverse auction
     pub highest: Option<Bid>,
     // winner of bid when the status is Closed
     pub winner: Option<Addr>,
+    // bids are rejected while paused
+    #[serde(default)]
+    pub paused: bool,
+    // the bid was voided without a winner, every bidder can retract
+    #[serde(default)]
+    pub emergency: bool,
+    // the commission sent to the fee collector so far, returned on cancel
+    #[serde(default)]
+    pub collected: Vec<Coin>,
+    // the minimum price to sell, closing below it ends without a winner
+    #[serde(default)]
+    pub reserve: Option<Reserve>,
+    // sha256 of a secret reserve, revealed into `reserve` on close
+    #[serde(default)]
+    pub reserve_commitment: Option<HexBinary>,
+    // the price selling right away, dropped once the bids get close to it
+    #[serde(default)]
+    pub buy_now: Option<BuyNow>,
+    // basis points of the highest bid to outbid it by, at least the tick
+    #[serde(default)]
+    pub increment_bps: Option<u16>,
+    // how the bids are placed and the winner charged, English if missing
+    #[serde(default)]
+    pub kind: AuctionKind,
+    // the bid outbid last by the highest bidder
+    #[serde(default)]
+    pub runner_up: Option<Bid>,
+    // the price charged to the winner on close
+    #[serde(default)]
+    pub clearing_price: Option<Coin>,
+    // the number of unit bids placed, orders equal unit prices
+    #[serde(default)]
+    pub unit_seq: u64,
 }
 
 impl State {
-    pub fn new(owner: Addr, name: String, tick: Coin, commission: Coin) -> Self {
+    pub fn new(
+        owner: Addr,
+        name: String,
+        denom: String,
+        tick: Coin,
+        commission: CommissionModel,
+    ) -> Self {
         Self {
-            owner,
+            owner: Some(owner),
+            pending_owner: None,
+            pending_expiry: None,
+            beneficiary: None,
             name,
+            denom,
+            cw20: None,
+            weights: vec![],
             tick,
             commission,
+            fee_collector: None,
             status: BidStatus::default(),
+            start: None,
+            end: None,
+            extension: None,
+            nft: None,
+            item: None,
             highest: None,
             winner: None,
+            paused: false,
+            emergency: false,
+            collected: vec![],
+            reserve: None,
+            reserve_commitment: None,
+            buy_now: None,
+            increment_bps: None,
+            kind: AuctionKind::default(),
+            runner_up: None,
+            clearing_price: None,
+            unit_seq: 0,
+        }
+    }
+
+    pub fn is_owner(&self, sender: &Addr) -> bool {
+        self.owner.as_ref() == Some(sender)
+    }
+
+    /// The address the winning bid is sent to on close
+    pub fn beneficiary(&self) -> Option<&Addr> {
+        self.beneficiary.as_ref().or(self.owner.as_ref())
+    }
+
+    /// The address the commission of each bid is sent to
+    pub fn fee_collector(&self) -> Option<&Addr> {
+        self.fee_collector.as_ref().or(self.owner.as_ref())
+    }
+
+    pub fn is_cw20(&self, denom: &str) -> bool {
+        self.cw20
+            .as_ref()
+            .is_some_and(|cw20| cw20.as_str() == denom)
+    }
+
+    /// The weight converting the denom into the bidding denom, None if not accepted
+    pub fn weight(&self, denom: &str) -> Option<Decimal> {
+        if denom == self.denom {
+            return Some(Decimal::one());
+        }
+
+        self.weights
+            .iter()
+            .find(|w| w.denom == denom)
+            .map(|w| w.weight)
+    }
+
+    /// Sum the coins in the bidding denom, rounding each coin down
+    pub fn normalize(&self, coins: &[Coin]) -> Result<Coin, ContractError> {
+        coins
+            .iter()
+            .try_fold(Coin::new(0, &self.denom), |mut acc, c| {
+                let weight =
+                    self.weight(&c.denom)
+                        .ok_or_else(|| ContractError::CoinSupportedOnlyErr {
+                            denom: c.denom.clone(),
+                        })?;
+
+                acc.amount += c.amount.mul_floor(weight);
+
+                Ok(acc)
+            })
+    }
+
+    pub fn status_at(&self, block: &BlockInfo) -> BidStatus {
+        match &self.status {
+            BidStatus::Closed {} => BidStatus::Closed {},
+            BidStatus::Cancelled {} => BidStatus::Cancelled {},
+            _ if !self.is_started(block) => BidStatus::NotStarted {},
+            _ => BidStatus::Opening {},
+        }
+    }
+
+    pub fn is_started(&self, block: &BlockInfo) -> bool {
+        let deposited = self.nft.is_none() || self.item.is_some();
+
+        deposited
+            && self
+                .start
+                .as_ref()
+                .is_none_or(|start| start.is_triggered(block))
+    }
+
+    pub fn is_ended(&self, block: &BlockInfo) -> bool {
+        self.end.as_ref().is_some_and(|end| end.is_expired(block))
+    }
+
+    /// The price the highest bidder pays if it wins, a second price is the runner-up plus
+    /// the tick, never below the reserve nor above the highest bid
+    pub fn winning_price(&self) -> Option<Coin> {
+        let highest = self.highest.as_ref()?;
+
+        let price = match self.kind {
+            AuctionKind::SecondPrice {} => {
+                let runner_up = self
+                    .runner_up
+                    .as_ref()
+                    .map(|bid| bid.bid.amount)
+                    .unwrap_or_default();
+                let reserve = self
+                    .reserve
+                    .as_ref()
+                    .map(|reserve| reserve.price)
+                    .unwrap_or_default();
+
+                (runner_up + self.tick.amount)
+                    .max(reserve)
+                    .min(highest.bid.amount)
+            }
+            _ => highest.bid.amount,
+        };
+
+        Some(Coin::new(price.u128(), &self.denom))
+    }
+
+    /// The price of a Dutch auction at the block, None for other kinds
+    pub fn current_price(&self, block: &BlockInfo) -> Option<Uint128> {
+        let AuctionKind::Dutch {
+            start_price,
+            floor_price,
+            decay,
+        } = &self.kind
+        else {
+            return None;
+        };
+
+        let elapsed = match (&self.start, decay.per()) {
+            (Some(Scheduled::AtHeight(start)), Duration::Height(_)) => {
+                block.height.saturating_sub(*start)
+            }
+            (Some(Scheduled::AtTime(start)), Duration::Time(_)) => {
+                block.time.seconds().saturating_sub(start.seconds())
+            }
+            _ => 0,
+        };
+
+        let price = start_price.saturating_sub(decay.dropped(elapsed));
+
+        Some(price.max(*floor_price))
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        matches!(self.kind, AuctionKind::SealedBid { .. })
+    }
+
+    /// The end of the reveal phase of a sealed bid
+    pub fn reveal_end(&self) -> Option<&Expiration> {
+        match &self.kind {
+            AuctionKind::SealedBid { reveal_end, .. } => Some(reveal_end),
+            _ => None,
         }
     }
+
+    /// Until when the owner can reveal a committed reserve after the end, anyone closes
+    /// without the reserve afterwards
+    pub fn reserve_reveal_end(&self) -> Option<Expiration> {
+        self.reserve_commitment.as_ref()?;
+
+        match self.end? {
+            Expiration::AtHeight(end) => Some(Expiration::AtHeight(end + RESERVE_REVEAL_BLOCKS)),
+            Expiration::AtTime(end) => {
+                Some(Expiration::AtTime(end.plus_seconds(RESERVE_REVEAL_SECONDS)))
+            }
+            Expiration::Never {} => None,
+        }
+    }
+
+    /// Whether the highest bid reaches the reserve price, true without a reserve,
+    /// false while a committed reserve is not revealed
+    pub fn is_reserve_met(&self) -> bool {
+        match (&self.reserve, &self.highest) {
+            (None, _) => self.reserve_commitment.is_none(),
+            (Some(reserve), Some(highest)) => highest.bid.amount >= reserve.price,
+            (Some(_), None) => false,
+        }
+    }
+
+    /// The total a bid must reach to become the highest, the highest bid plus the tick
+    /// or the increment bps of it, whichever is larger. A penny auction starts at the tick
+    pub fn minimum_total(&self) -> Uint128 {
+        match &self.highest {
+            None if matches!(self.kind, AuctionKind::Penny { .. }) => {
+                self.tick.amount.max(Uint128::one())
+            }
+            None => Uint128::one(),
+            Some(highest) => {
+                let by_bps = self
+                    .increment_bps
+                    .map(|bps| highest.bid.amount.multiply_ratio(bps, MAX_BPS))
+                    .unwrap_or_default();
+
+                highest.bid.amount + by_bps.max(self.tick.amount).max(Uint128::one())
+            }
+        }
+    }
+
+    /// Whether the highest bid reaches the buy now price
+    pub fn is_bought_now(&self) -> bool {
+        match (&self.buy_now, &self.highest) {
+            (Some(buy_now), Some(highest)) => highest.bid.amount >= buy_now.price,
+            _ => false,
+        }
+    }
+
+    /// Whether the winner pays the clearing price out of its deposit, a bid reaching the
+    /// buy now price pays that price only
+    pub fn pays_clearing_price(&self) -> bool {
+        self.kind.pays_clearing_price() || self.is_bought_now()
+    }
+
+    /// Whether the winner retracts what is left of its deposit after close
+    pub fn winner_retracts(&self) -> bool {
+        self.kind.winner_retracts() || self.is_bought_now()
+    }
+
+    /// Whether the highest bid exceeds the expire ratio of the buy now price
+    pub fn is_buy_now_expired(&self) -> bool {
+        match (&self.buy_now, &self.highest) {
+            (
+                Some(BuyNow {
+                    price,
+                    expire_ratio: Some(ratio),
+                }),
+                Some(highest),
+            ) => highest.bid.amount > price.mul_floor(*ratio),
+            _ => false,
+        }
+    }
+
+    /// Push the end time forward if the bid lands within the extension window,
+    /// returns the new end time if it was extended
+    pub fn extend_end(&mut self, block: &BlockInfo) -> Option<Timestamp> {
+        let (Some(extension), Some(Expiration::AtTime(end))) = (&self.extension, &self.end) else {
+            return None;
+        };
+
+        if block.time.plus_seconds(extension.window) < *end {
+            return None;
+        }
+
+        let mut extended = end.plus_seconds(extension.duration);
+        if let Some(hard_cap) = extension.hard_cap {
+            extended = extended.min(hard_cap);
+        }
+
+        if extended <= *end {
+            return None;
+        }
+
+        self.end = Some(Expiration::AtTime(extended));
+
+        Some(extended)
+    }
+
+    /// Restart the countdown of a penny auction, the configured end stays the earliest
+    /// end, returns the new end time if it moved
+    pub fn reset_countdown(&mut self, block: &BlockInfo) -> Option<Timestamp> {
+        let AuctionKind::Penny { countdown } = self.kind else {
+            return None;
+        };
+
+        let end = block.time.plus_seconds(countdown);
+        if matches!(self.end, Some(Expiration::AtTime(current)) if current >= end) {
+            return None;
+        }
+
+        self.end = Some(Expiration::AtTime(end));
+
+        Some(end)
+    }
+}
+
+/// How the commission of a bid is computed from the bid amount, in the bidding denom
+#[cw_serde]
+pub enum CommissionModel {
+    /// The same amount for every bid
+    Flat { amount: Uint128 },
+    /// Basis points of the bid, clamped between `min` and `max`
+    Percentage {
+        bps: u16,
+        min: Option<Uint128>,
+        max: Option<Uint128>,
+    },
+    /// Basis points of the bracket the bid falls in, brackets are sorted by `from`
+    Tiered { tiers: Vec<CommissionTier> },
+}
+
+impl CommissionModel {
+    pub fn flat(amount: u128) -> Self {
+        Self::Flat {
+            amount: amount.into(),
+        }
+    }
+
+    /// The commission of a bid of the given amount
+    pub fn commission(&self, amount: Uint128) -> Uint128 {
+        match self {
+            Self::Flat { amount } => *amount,
+            Self::Percentage { bps, min, max } => {
+                let commission = amount.multiply_ratio(*bps, MAX_BPS);
+                let commission = min.map_or(commission, |min| commission.max(min));
+
+                max.map_or(commission, |max| commission.min(max))
+            }
+            Self::Tiered { tiers } => tiers
+                .iter()
+                .rev()
+                .find(|tier| tier.from <= amount)
+                .map(|tier| amount.multiply_ratio(tier.bps, MAX_BPS))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The smallest bid keeping at least `net` after the commission, None if the
+    /// commission takes it all
+    pub fn gross(&self, net: Uint128) -> Option<Uint128> {
+        // a lower bps on a higher bracket may keep enough from the start of that bracket
+        let brackets = match self {
+            Self::Tiered { tiers } => tiers.iter().map(|tier| tier.from).collect(),
+            _ => vec![],
+        };
+
+        let mut from = net;
+        for until in brackets.into_iter().filter(|until| *until > net) {
+            if let Some(gross) = self.gross_within(net, from, Some(until)) {
+                return Some(gross);
+            }
+            from = until;
+        }
+
+        self.gross_within(net, from, None)
+    }
+
+    // Within a bracket the commission grows no faster than the bid, so adding the
+    // shortfall never overshoots the smallest gross
+    fn gross_within(&self, net: Uint128, from: Uint128, until: Option<Uint128>) -> Option<Uint128> {
+        let mut gross = from;
+
+        for _ in 0..MAX_GROSS_ROUNDS {
+            if until.is_some_and(|until| gross >= until) {
+                return None;
+            }
+
+            let kept = gross.saturating_sub(self.commission(gross));
+            if kept >= net {
+                return Some(gross);
+            }
+
+            gross = gross.checked_add(net - kept).ok()?;
+        }
+
+        None
+    }
+}
+
+const MAX_GROSS_ROUNDS: usize = 256;
+
+/// Bids from `from` on pay `bps` basis points
+#[cw_serde]
+pub struct CommissionTier {
+    pub from: Uint128,
+    pub bps: u16,
+}
+
+pub const MAX_BPS: u16 = 10_000;
+
+// how long after the end the owner has to reveal a committed reserve
+pub const RESERVE_REVEAL_BLOCKS: u64 = 14_400;
+pub const RESERVE_REVEAL_SECONDS: u64 = 86_400;
+
+#[cw_serde]
+pub struct AuctionItem {
+    pub contract: Addr,
+    pub token_id: String,
+}
+
+/// The minimum price to sell, in the bidding denom
+#[cw_serde]
+pub struct Reserve {
+    pub price: Uint128,
+    // bidders only learn whether the reserve is met, not the price
+    pub hidden: bool,
+}
+
+impl Reserve {
+    /// The commitment of a secret reserve, see [`commitment`]
+    pub fn commitment(price: Uint128, salt: &[u8]) -> HexBinary {
+        commitment(price, salt)
+    }
+}
+
+/// Sell right away to the first bid reaching the price, in the bidding denom
+#[cw_serde]
+pub struct BuyNow {
+    pub price: Uint128,
+    // drop the offer once the highest bid exceeds this fraction of the price
+    pub expire_ratio: Option<Decimal>,
+}
+
+/// How bids are placed and the winner picked
+#[cw_serde]
+pub enum AuctionKind {
+    /// Open ascending bids, the highest total wins
+    English {},
+    /// Bids are committed with a deposit until `end` and revealed until `reveal_end`,
+    /// the highest reveal wins and pays the revealed amount
+    SealedBid {
+        reveal_end: Expiration,
+        // basis points of the deposit forfeited by a bidder who never reveals
+        forfeit_bps: u16,
+    },
+    /// Open ascending bids, the highest bidder wins and pays the runner-up plus the tick
+    SecondPrice {},
+    /// The price drops from `start_price` to `floor_price` since the start, the first
+    /// buyer wins at the current price
+    Dutch {
+        start_price: Uint128,
+        floor_price: Uint128,
+        decay: Decay,
+    },
+    /// Sells `quantity` identical units, the top unit bids are allocated and every winner
+    /// pays the lowest winning unit price
+    MultiUnit { quantity: u64 },
+    /// Every bid raises the price by the tick and pays the commission as a non-refundable
+    /// fee, the end resets to `countdown` seconds after the last bid, who wins. A
+    /// configured end is kept as the earliest end
+    Penny { countdown: u64 },
+    /// The owner buys with an escrowed `budget`, suppliers post a `bond` and ask downward,
+    /// the lowest ask wins and is paid from the budget
+    Reverse { budget: Uint128, bond: Uint128 },
+}
+
+impl AuctionKind {
+    /// Whether bids are placed with `Bidding`
+    pub fn accepts_bids(&self) -> bool {
+        matches!(
+            self,
+            Self::English {} | Self::SecondPrice {} | Self::Penny { .. }
+        )
+    }
+
+    /// Whether the commission goes back to the bidders on cancel
+    pub fn refunds_commission(&self) -> bool {
+        !matches!(self, Self::Penny { .. })
+    }
+
+    /// Whether the winner retracts its deposit, or what is left of it, after close
+    pub fn winner_retracts(&self) -> bool {
+        self.pays_clearing_price() || matches!(self, Self::Reverse { .. })
+    }
+
+    /// Whether the winner pays the clearing price out of its deposit and retracts the rest
+    pub fn pays_clearing_price(&self) -> bool {
+        matches!(self, Self::SealedBid { .. } | Self::SecondPrice {})
+    }
+}
+
+impl Default for AuctionKind {
+    fn default() -> Self {
+        Self::English {}
+    }
+}
+
+/// How the price of a Dutch auction drops, per block height or second
+#[cw_serde]
+pub enum Decay {
+    /// Drops `amount` every `per`, prorated in between
+    Linear { amount: Uint128, per: Duration },
+    /// Drops `amount` once every full `per`
+    Step { amount: Uint128, per: Duration },
+}
+
+impl Decay {
+    pub fn per(&self) -> &Duration {
+        match self {
+            Self::Linear { per, .. } | Self::Step { per, .. } => per,
+        }
+    }
+
+    /// The price dropped after `elapsed` blocks or seconds
+    pub fn dropped(&self, elapsed: u64) -> Uint128 {
+        let per = match self.per() {
+            Duration::Height(per) | Duration::Time(per) => (*per).max(1),
+        };
+
+        match self {
+            Self::Linear { amount, .. } => amount
+                .checked_multiply_ratio(elapsed, per)
+                .unwrap_or(Uint128::MAX),
+            Self::Step { amount, .. } => amount.saturating_mul(Uint128::from(elapsed / per)),
+        }
+    }
+}
+
+/// A sealed bid, `commitment(amount, salt)` of the bid amount
+#[cw_serde]
+pub struct Commitment {
+    pub hash: HexBinary,
+    pub revealed: bool,
+}
+
+/// `sha256(amount || salt)` with the amount as 16 big endian bytes
+pub fn commitment(amount: Uint128, salt: &[u8]) -> HexBinary {
+    let mut hasher = Sha256::new();
+    hasher.update(amount.u128().to_be_bytes());
+    hasher.update(salt);
+
+    HexBinary::from(hasher.finalize().as_slice())
+}
+
+/// One `denom` is worth `weight` of the bidding denom
+#[cw_serde]
+pub struct DenomWeight {
+    pub denom: String,
+    pub weight: Decimal,
+}
+
+/// A bid landing within `window` seconds of the end pushes the end forward by
+/// `duration` seconds, but never beyond `hard_cap`
+#[cw_serde]
+pub struct Extension {
+    pub window: u64,
+    pub duration: u64,
+    pub hard_cap: Option<Timestamp>,
+}
+
+/// The deposits of a bidder, split into the net bid and the commission paid
+#[cw_serde]
+#[derive(Default)]
+pub struct Deposit {
+    // the coins bid after commission, paid to the owner or refunded
+    pub bid: Vec<Coin>,
+    // the commission of every bid, already sent to the fee collector
+    pub commission: Vec<Coin>,
 }
 
 #[cw_serde]
@@ -42,10 +654,14 @@ pub struct Bid {
     pub bidder: Addr,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[cw_serde]
+#[derive(Eq)]
 pub enum BidStatus {
+    NotStarted {},
     Opening {},
     Closed {},
+    // cancelled by the owner, no winner and every deposit is refunded in full
+    Cancelled {},
 }
 
 impl Default for BidStatus {
@@ -55,11 +671,149 @@ impl Default for BidStatus {
 }
 
 impl BidStatus {
+    /// No more bids are accepted, closed or cancelled
     pub fn is_closed(&self) -> bool {
-        matches!(self, Self::Closed {})
+        matches!(self, Self::Closed {} | Self::Cancelled {})
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled {})
+    }
+}
+
+/// Who may open auctions in this contract
+#[cw_serde]
+pub struct Config {
+    // the contract admin, updates the creators
+    pub admin: Addr,
+    // addresses allowed to open auctions, anyone if missing
+    pub creators: Option<Vec<Addr>>,
+}
+
+impl Config {
+    pub fn can_open(&self, sender: &Addr) -> bool {
+        self.creators
+            .as_ref()
+            .is_none_or(|creators| creators.contains(sender))
     }
 }
 
 // Define the state storage
-pub const STATE: Item<State> = Item::new("state");
-pub const BIDDINGS: Map<Addr, Coin> = Map::new("bids");
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// the last auction id, ids start at 1
+pub const AUCTION_SEQ: Item<u64> = Item::new("auction_seq");
+
+pub const STATE: Map<u64, State> = Map::new("auctions");
+// all coins deposited by each bidder, one coin per denom
+pub const BIDDINGS: Map<(u64, Addr), Deposit> = Map::new("biddings");
+// the sealed bid of each bidder
+pub const COMMITMENTS: Map<(u64, Addr), Commitment> = Map::new("commitments");
+
+/// A bid for several units of a multi-unit auction
+#[cw_serde]
+pub struct UnitBid {
+    pub auction_id: u64,
+    pub bidder: Addr,
+    pub price: Uint128,
+    pub quantity: u64,
+    // the placement order, the earlier bid wins a tie
+    pub seq: u64,
+    // the units won, set on close
+    pub allocated: u64,
+}
+
+// the auction, then the unit price and the reversed placement order
+pub type UnitRank = (u64, (u128, u64));
+
+// a bidder and the units it is allocated
+pub type Allocation = (Addr, u64);
+
+impl UnitBid {
+    /// Ranks higher prices first, then earlier bids, when iterated in descending order
+    pub fn rank(&self) -> UnitRank {
+        (self.auction_id, (self.price.u128(), u64::MAX - self.seq))
+    }
+}
+
+pub struct UnitBidIndexes<'a> {
+    pub rank: MultiIndex<'a, UnitRank, UnitBid, (u64, Addr)>,
+}
+
+impl<'a> IndexList<UnitBid> for UnitBidIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<UnitBid>> + '_> {
+        let v: Vec<&dyn Index<UnitBid>> = vec![&self.rank];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn unit_bids<'a>() -> IndexedMap<'a, (u64, Addr), UnitBid, UnitBidIndexes<'a>> {
+    let indexes = UnitBidIndexes {
+        rank: MultiIndex::new(|_pk, bid| bid.rank(), "unit_bids", "unit_bids__rank"),
+    };
+
+    IndexedMap::new("unit_bids", indexes)
+}
+
+/// Allocate the units to the top ranked bids, with the lowest allocated unit price
+pub fn unit_allocations(
+    storage: &dyn Storage,
+    auction_id: u64,
+    quantity: u64,
+) -> StdResult<(Vec<Allocation>, Option<Uint128>)> {
+    let mut remaining = quantity;
+    let mut allocations = vec![];
+    let mut price = None;
+
+    let ranked =
+        unit_bids()
+            .idx
+            .rank
+            .sub_prefix(auction_id)
+            .range(storage, None, None, Order::Descending);
+
+    for item in ranked {
+        if remaining == 0 {
+            break;
+        }
+
+        let (_, bid) = item?;
+        let units = bid.quantity.min(remaining);
+
+        remaining -= units;
+        price = Some(bid.price);
+        allocations.push((bid.bidder, units));
+    }
+
+    Ok((allocations, price))
+}
+
+/// The storage layout of v0.1, a single auction bid in ATOM
+pub mod v0_1 {
+    use cosmwasm_std::{Addr, Coin};
+    use cw_storage_plus::{Item, Map};
+    use serde::{Deserialize, Serialize};
+
+    use super::Bid;
+
+    #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+    pub struct State {
+        pub owner: Addr,
+        pub name: String,
+        pub tick: Coin,
+        pub commission: Coin,
+        pub status: BidStatus,
+        pub highest: Option<Bid>,
+        pub winner: Option<Addr>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+    pub enum BidStatus {
+        Opening {},
+        Closed {},
+    }
+
+    pub const STATE: Item<State> = Item::new("state");
+    // the sum of all bids of each bidder, the commission is deducted on retract
+    pub const BIDDINGS: Map<Addr, Coin> = Map::new("bids");
+}